@@ -6,6 +6,7 @@ pub enum BackendMessage<T> {
     FinishedFetching,
     Clear,
     New(T),
+    Update(T),
     Remove(T),
     Error(String),
 }
@@ -0,0 +1,128 @@
+// Characters that commonly surround the timestamp and title of a chapter, e.g. "- 04:32 - Topic"
+const SEPARATORS: [char; 10] = ['-', '–', '—', ':', '|', '•', '*', '(', ')', ' '];
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Chapter {
+    // Start of the chapter in seconds
+    pub start: u32,
+    pub title: String,
+}
+
+// Parses chapters from lines in the description that start or end with a timestamp. At least two
+// chapters are required since a single timestamp is more likely to be a reference to a moment.
+pub fn parse(description: &str) -> Vec<Chapter> {
+    let mut chapters: Vec<Chapter> = description.lines().filter_map(parse_line).collect();
+    chapters.sort();
+    chapters.dedup_by_key(|chapter| chapter.start);
+
+    if chapters.len() >= 2 {
+        chapters
+    } else {
+        vec![]
+    }
+}
+
+fn parse_line(line: &str) -> Option<Chapter> {
+    let line = line.trim_matches(SEPARATORS);
+    let (first, rest) = line.split_once(' ').unwrap_or((line, ""));
+    let (rest_start, last) = line.rsplit_once(' ').unwrap_or(("", line));
+
+    let (start, title) = match parse_timestamp(first) {
+        Some(start) => (start, rest.trim_start_matches(SEPARATORS)),
+        None => (
+            parse_timestamp(last)?,
+            rest_start.trim_end_matches(SEPARATORS),
+        ),
+    };
+
+    Some(Chapter {
+        start,
+        title: title.to_owned(),
+    })
+}
+
+// Parses timestamps in the formats m:ss, mm:ss and h:mm:ss into seconds
+fn parse_timestamp(timestamp: &str) -> Option<u32> {
+    let timestamp = timestamp.trim_matches(SEPARATORS);
+    let parts: Vec<&str> = timestamp.split(':').collect();
+    if !(2..=3).contains(&parts.len()) || parts.iter().any(|part| part.is_empty()) {
+        return None;
+    }
+
+    let mut seconds = 0;
+    for (i, part) in parts.iter().enumerate() {
+        if !part.chars().all(|character| character.is_ascii_digit()) {
+            return None;
+        }
+
+        let value = part.parse::<u32>().ok()?;
+        if i > 0 && (part.len() != 2 || value >= 60) {
+            return None;
+        }
+        seconds = seconds * 60 + value;
+    }
+
+    Some(seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chapter(start: u32, title: &str) -> Chapter {
+        Chapter {
+            start,
+            title: title.to_owned(),
+        }
+    }
+
+    #[test]
+    fn parses_timestamp_formats() {
+        assert_eq!(parse_timestamp("0:05"), Some(5));
+        assert_eq!(parse_timestamp("4:32"), Some(272));
+        assert_eq!(parse_timestamp("04:32"), Some(272));
+        assert_eq!(parse_timestamp("12:00"), Some(720));
+        assert_eq!(parse_timestamp("1:02:03"), Some(3723));
+        assert_eq!(parse_timestamp("01:02:03"), Some(3723));
+        assert_eq!(parse_timestamp("(1:02)"), Some(62));
+    }
+
+    #[test]
+    fn rejects_invalid_timestamps() {
+        assert_eq!(parse_timestamp("5"), None);
+        assert_eq!(parse_timestamp("4:3"), None);
+        assert_eq!(parse_timestamp("4:60"), None);
+        assert_eq!(parse_timestamp("1:60:00"), None);
+        assert_eq!(parse_timestamp("1:2:3:4"), None);
+        assert_eq!(parse_timestamp(":30"), None);
+        assert_eq!(parse_timestamp("a:30"), None);
+        assert_eq!(parse_timestamp("+1:30"), None);
+    }
+
+    #[test]
+    fn parses_timestamps_at_start_or_end_of_line() {
+        let description = "Intro text\n0:00 Intro\n- 04:32 - Topic\nOutro | 1:02:03\n(10:00) Bonus";
+        assert_eq!(
+            parse(description),
+            vec![
+                chapter(0, "Intro"),
+                chapter(272, "Topic"),
+                chapter(600, "Bonus"),
+                chapter(3723, "Outro"),
+            ]
+        );
+    }
+
+    #[test]
+    fn requires_two_chapters() {
+        assert_eq!(parse("See 1:30 for the best part\n1:30"), vec![]);
+    }
+
+    #[test]
+    fn removes_duplicate_starts() {
+        assert_eq!(
+            parse("0:00 Intro\n00:00 Start\n1:00 End"),
+            vec![chapter(0, "Intro"), chapter(60, "End")]
+        );
+    }
+}
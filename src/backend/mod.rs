@@ -1,12 +1,15 @@
 pub mod rss;
 
+pub mod chapter;
+
 pub mod channel;
 
-use crate::config_error::ConfigError;
+use crate::{config_error::ConfigError, history::HistoryError};
 use rss::RssBackendError;
 
 use async_trait::async_trait;
-use chrono::{DateTime, FixedOffset};
+use chrono::{DateTime, FixedOffset, Local};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -16,6 +19,9 @@ pub enum BackendError {
 
     #[error("RSS backend error: {}", _0)]
     RssBackendError(#[from] RssBackendError),
+
+    #[error("History error: {}", _0)]
+    History(#[from] HistoryError),
 }
 
 #[async_trait]
@@ -28,7 +34,7 @@ pub trait Backend {
     fn refetch(&self);
 }
 
-#[derive(Clone, Ord, PartialOrd, Eq, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Video {
     pub date: DateTime<FixedOffset>,
     pub title: String,
@@ -36,5 +42,55 @@ pub struct Video {
     pub author: String,
     pub feed_url: String,
     pub description: String,
+    // Length in seconds, 0 if unknown
     pub length: u32,
+    pub thumbnail: Option<String>,
+    pub views: Option<u64>,
+    pub rating: Option<Rating>,
+    pub kind: VideoKind,
+}
+
+impl Video {
+    // A video that's only known by its url, such as one queued from outside of tuibe
+    pub fn from_url(url: &str) -> Self {
+        Self {
+            date: Local::now().fixed_offset(),
+            title: url.to_owned(),
+            url: url.to_owned(),
+            author: String::new(),
+            feed_url: String::new(),
+            description: String::new(),
+            length: 0,
+            thumbnail: None,
+            views: None,
+            rating: None,
+            kind: VideoKind::Regular,
+        }
+    }
+
+    // The channel page, derived from the channel_id of YouTube channel feeds
+    pub fn channel_url(&self) -> Option<String> {
+        let (_, query) = self.feed_url.split_once("channel_id=")?;
+        let channel_id = query.split('&').next().filter(|id| !id.is_empty())?;
+        Some(format!("https://www.youtube.com/channel/{channel_id}"))
+    }
+}
+
+// Titles and names can contain newlines and tabs, which would break line based output
+pub fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VideoKind {
+    Regular,
+    Short,
+    Live,
+    Upcoming,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rating {
+    pub average: f32,
+    pub count: u64,
 }
@@ -1,18 +1,30 @@
 use super::{
-    Backend, BackendError, Video,
+    Backend, BackendError, Rating, Video, VideoKind,
     channel::{BackendMessage, BackendReceiver, BackendSender},
 };
-use crate::{config_error::ConfigError, file_handler::ConfigFileHandler};
+use crate::{
+    config_error::ConfigError,
+    file_handler::{self, ConfigFileHandler},
+    hooks::{HookEvent, Hooks},
+    yt_dlp::{self, Probe},
+};
 
 use async_trait::async_trait;
-use atom_syndication::Entry;
+use atom_syndication::{Entry, extension::Extension};
+use log::warn;
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::Arc,
+};
 use thiserror::Error;
-use tokio::fs;
+use tokio::{fs, sync::Semaphore, task::JoinHandle};
 
 const CONFIG_NAME: &str = "rss";
+const CACHE_NAME: &str = "feeds.json";
+const SEEN_NAME: &str = "seen";
+const CONCURRENT_PROBES: usize = 4;
 
 #[derive(Debug, Error)]
 pub enum RssBackendError {
@@ -33,20 +45,114 @@ pub enum RssBackendError {
 
     #[error("Failed to parse YouTube takeout")]
     ParseYoutubeTakeout,
+
+    #[error("Failed to write feed cache: {}", _0)]
+    WriteCache(#[source] std::io::Error),
+
+    #[error("Failed to serialize feed cache: {}", _0)]
+    SerializeCache(#[from] serde_json::Error),
+}
+
+// Urls of the videos of every feed as of the last fetch that ran the new video hook. It's kept in
+// the state dir so that the hook runs once per video, also when `tuibe daemon` runs as well.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SeenVideos {
+    feeds: BTreeMap<String, BTreeSet<String>>,
 }
 
 #[derive(Clone, Default, Serialize, Deserialize)]
 pub struct RssConfig {
-    pub feeds: Vec<String>,
+    pub feeds: Vec<FeedConfig>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(from = "FeedConfigEntry", into = "FeedConfigEntry")]
+pub struct FeedConfig {
+    pub url: String,
+    // Player profile or command template used for videos in this feed
+    pub player: Option<String>,
+    // Tags that feeds can be filtered by from the command line
+    pub tags: Vec<String>,
+    // Send desktop notifications for new videos when running as a daemon
+    pub notify: bool,
+}
+
+impl FeedConfig {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_owned(),
+            player: None,
+            tags: vec![],
+            notify: false,
+        }
+    }
+}
+
+// Feeds without any options are stored as plain urls
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum FeedConfigEntry {
+    Url(String),
+    Table {
+        url: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        player: Option<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        tags: Vec<String>,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        notify: bool,
+    },
+}
+
+impl From<FeedConfigEntry> for FeedConfig {
+    fn from(value: FeedConfigEntry) -> Self {
+        match value {
+            FeedConfigEntry::Url(url) => Self::new(&url),
+            FeedConfigEntry::Table {
+                url,
+                player,
+                tags,
+                notify,
+            } => Self {
+                url,
+                player,
+                tags,
+                notify,
+            },
+        }
+    }
+}
+
+impl From<FeedConfig> for FeedConfigEntry {
+    fn from(value: FeedConfig) -> Self {
+        if value.player.is_none() && value.tags.is_empty() && !value.notify {
+            return Self::Url(value.url);
+        }
+
+        Self::Table {
+            url: value.url,
+            player: value.player,
+            tags: value.tags,
+            notify: value.notify,
+        }
+    }
 }
 
-#[derive(Clone, PartialEq, PartialOrd, Eq, Ord)]
+#[derive(Clone, PartialEq, PartialOrd, Eq, Ord, Serialize, Deserialize)]
 pub struct Feed {
     pub title: String,
     pub url: String,
 }
 
-#[derive(Clone, Default)]
+// Feeds and videos of the last fetch, which are also cached to show them before fetching
+pub struct FetchedVideos {
+    pub videos: Vec<Video>,
+    // Errors of the feeds that failed to fetch, prefixed with their urls
+    pub errors: Vec<String>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
 struct RssBackendData {
     videos: Vec<Video>,
     feeds: Vec<Feed>,
@@ -56,6 +162,14 @@ struct RssBackendData {
 struct RssBackendInner {
     config: RssConfig,
     data: Option<RssBackendData>,
+    // Results of probing videos, kept to avoid probing the same video again when refetching
+    probes: HashMap<String, Probe>,
+    hooks: Hooks,
+}
+
+struct VideoProbe {
+    yt_dlp: String,
+    permits: Semaphore,
 }
 
 pub struct RssBackend {
@@ -63,31 +177,60 @@ pub struct RssBackend {
     file_handler: tokio::sync::Mutex<ConfigFileHandler<RssConfig>>,
     video_sender: Arc<BackendSender<Video>>,
     feed_sender: Arc<BackendSender<Feed>>,
+    video_probe: Mutex<Option<Arc<VideoProbe>>>,
 }
 
 impl RssBackend {
+    pub fn with_video_probe(self, yt_dlp: Option<String>) -> Self {
+        self.set_video_probe(yt_dlp);
+        self
+    }
+
+    // Probes the duration and kind of videos using yt-dlp as they're fetched from now on
+    pub fn set_video_probe(&self, yt_dlp: Option<String>) {
+        *self.video_probe.lock() = yt_dlp.map(|yt_dlp| {
+            Arc::new(VideoProbe {
+                yt_dlp,
+                permits: Semaphore::new(CONCURRENT_PROBES),
+            })
+        });
+    }
+
+    pub fn with_hooks(self, hooks: Hooks) -> Self {
+        self.set_hooks(hooks);
+        self
+    }
+
+    pub fn set_hooks(&self, hooks: Hooks) {
+        self.inner.lock().hooks = hooks;
+    }
+
     pub async fn add_feed(&self, url: &str) -> Result<(), BackendError> {
         {
             let inner = self.inner.lock();
-            if inner.config.feeds.contains(&url.to_string()) {
+            if inner.config.feeds.iter().any(|feed| feed.url == url) {
                 return Ok(());
             }
         }
 
+        let video_probe = self.video_probe.lock().clone();
         Self::fetch_feed(
             url,
             self.inner.clone(),
             self.video_sender.clone(),
             self.feed_sender.clone(),
+            video_probe,
         )
         .await?;
-        let rss_backend = {
-            let mut inner = self.inner.lock();
-            inner.config.feeds.push(url.to_owned());
-            inner.config.clone()
-        };
-
-        self.save(&rss_backend).await?;
+        // The fetched videos are removed again if the feed can't be saved
+        let result = self
+            .update_config(|config| config.feeds.push(FeedConfig::new(url)))
+            .await;
+        if let Err(error) = result {
+            self.remove_feed_data(url);
+            return Err(error.into());
+        }
+        self.run_feed_added_hook(url);
         Ok(())
     }
 
@@ -95,7 +238,7 @@ impl RssBackend {
         let content = fs::read_to_string(&path)
             .await
             .map_err(RssBackendError::ReadYoutubeTakeout)?;
-        let mut urls: Vec<String> = content
+        let mut feeds: Vec<FeedConfig> = content
             .trim()
             .split('\n')
             .skip(1)
@@ -104,50 +247,194 @@ impl RssBackend {
                     .split(',')
                     .next()
                     .ok_or(RssBackendError::ParseYoutubeTakeout)?;
-                Ok(format!(
+                Ok(FeedConfig::new(&format!(
                     "https://www.youtube.com/feeds/videos.xml?channel_id={}",
                     channel_id
-                ))
+                )))
             })
             .collect::<Result<Vec<_>, RssBackendError>>()?;
 
-        let config = {
+        self.update_config(|config| config.feeds.append(&mut feeds))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn remove_feed(&self, url: &str) -> Result<(), BackendError> {
+        self.update_config(|config| config.feeds.retain(|feed| feed.url != url))
+            .await?;
+        self.remove_feed_data(url);
+        Ok(())
+    }
+
+    // Reads rss.toml again if it's been changed by others, and fetches the feeds that have been
+    // added to it and removes the ones that have been removed. Returns whether it had changed.
+    pub async fn reload_config(&self) -> Result<bool, BackendError> {
+        let Some(config) = self.file_handler.lock().await.reload().await? else {
+            return Ok(false);
+        };
+
+        let (added, removed) = {
             let mut inner = self.inner.lock();
-            inner.config.feeds.append(&mut urls);
-            inner.config.clone()
+            let added = Self::missing_feeds(&config, &inner.config);
+            let removed = Self::missing_feeds(&inner.config, &config);
+            inner.config = config;
+            (added, removed)
         };
 
-        self.save(&config).await?;
-        Ok(())
+        for url in removed {
+            self.remove_feed_data(&url);
+        }
+        for url in added {
+            self.fetch_in_background(url.clone());
+            self.run_feed_added_hook(&url);
+        }
+        Ok(true)
     }
 
-    pub async fn remove_feed(&self, url: &str) -> Result<(), BackendError> {
-        let new_config = {
+    // Urls of the feeds in the first config that aren't in the second one
+    fn missing_feeds(config: &RssConfig, other: &RssConfig) -> Vec<String> {
+        config
+            .feeds
+            .iter()
+            .filter(|feed| !other.feeds.iter().any(|other| other.url == feed.url))
+            .map(|feed| feed.url.clone())
+            .collect()
+    }
+
+    fn remove_feed_data(&self, url: &str) {
+        let mut inner = self.inner.lock();
+        if let Some(ref mut data) = inner.data {
+            data.feeds.retain(|feed| {
+                let keep = feed.url != url;
+                if !keep {
+                    self.feed_sender.send(BackendMessage::Remove(feed.clone()));
+                }
+                keep
+            });
+            data.videos.retain(|video| {
+                let keep = video.feed_url != url;
+                if !keep {
+                    self.video_sender
+                        .send(BackendMessage::Remove(video.clone()));
+                }
+                keep
+            });
+        }
+    }
+
+    pub fn feed_configs(&self) -> Vec<FeedConfig> {
+        self.inner.lock().config.feeds.clone()
+    }
+
+    pub fn video(&self, url: &str) -> Option<Video> {
+        let inner = self.inner.lock();
+        inner
+            .data
+            .as_ref()
+            .and_then(|data| data.videos.iter().find(|video| video.url == url))
+            .cloned()
+    }
+
+    // The player configured for the feed, if any
+    pub fn feed_player(&self, feed_url: &str) -> Option<String> {
+        let inner = self.inner.lock();
+        inner
+            .config
+            .feeds
+            .iter()
+            .find(|feed| feed.url == feed_url)
+            .and_then(|feed| feed.player.clone())
+    }
+
+    // Fetches every feed and returns the videos once all of them have been fetched. Feeds that
+    // fail to fetch are reported and keep the videos loaded from the cache, if any.
+    pub async fn fetch_videos(&self) -> Result<FetchedVideos, BackendError> {
+        let urls: Vec<String> = {
             let mut inner = self.inner.lock();
-            inner.config.feeds.retain(|feed| feed != url);
+            if inner.data.is_none() {
+                inner.data = Some(Default::default());
+            }
+            inner
+                .config
+                .feeds
+                .iter()
+                .map(|feed| feed.url.clone())
+                .collect()
+        };
 
-            if let Some(ref mut data) = inner.data {
-                data.feeds.retain(|feed| {
-                    let keep = feed.url != url;
-                    if !keep {
-                        self.feed_sender.send(BackendMessage::Remove(feed.clone()));
-                    }
-                    keep
-                });
-                data.videos.retain(|video| {
-                    let keep = video.feed_url != url;
-                    if !keep {
-                        self.video_sender
-                            .send(BackendMessage::Remove(video.clone()));
-                    }
-                    keep
-                });
+        let handles: Vec<_> = urls
+            .iter()
+            .map(|url| {
+                let url = url.clone();
+                let inner = self.inner.clone();
+                let video_sender = self.video_sender.clone();
+                let feed_sender = self.feed_sender.clone();
+                tokio::spawn(async move {
+                    Self::fetch_feed(&url, inner, video_sender, feed_sender, None).await
+                })
+            })
+            .collect();
+
+        let mut fetched = vec![];
+        let mut errors = vec![];
+        for (url, handle) in urls.into_iter().zip(handles) {
+            match handle.await.map_err(RssBackendError::from)? {
+                Ok(()) => fetched.push(url),
+                Err(error) => errors.push(format!("{url}: {error}")),
+            }
+        }
+
+        Self::run_new_video_hooks(&fetched, self.inner.clone(), self.video_sender.clone()).await?;
+        let inner = self.inner.lock();
+        let videos = inner
+            .data
+            .as_ref()
+            .map(|data| data.videos.clone())
+            .unwrap_or_default();
+        Ok(FetchedVideos { videos, errors })
+    }
+
+    // Shows the feeds and videos of the last fetch until they're fetched again. Returns the cached
+    // videos if there was a cache to load. Feeds that have been removed since are left out.
+    pub async fn load_cache(&self) -> Result<Option<Vec<Video>>, BackendError> {
+        let path = file_handler::find_cache_dir()?.join(CACHE_NAME);
+        let Ok(content) = fs::read_to_string(&path).await else {
+            return Ok(None);
+        };
+
+        let mut data: RssBackendData = match serde_json::from_str(&content) {
+            Ok(data) => data,
+            Err(error) => {
+                warn!("Ignoring invalid feed cache: {}", error);
+                return Ok(None);
             }
+        };
+
+        let mut inner = self.inner.lock();
+        let is_configured = |url: &str| inner.config.feeds.iter().any(|feed| feed.url == url);
+        data.feeds.retain(|feed| is_configured(&feed.url));
+        data.videos.retain(|video| is_configured(&video.feed_url));
 
-            inner.config.clone()
+        let videos = data.videos.clone();
+        if inner.data.is_none() {
+            inner.data = Some(data);
+        }
+        Ok(Some(videos))
+    }
+
+    pub async fn save_cache(&self) -> Result<(), BackendError> {
+        let Some(data) = self.inner.lock().data.clone() else {
+            return Ok(());
         };
+        let content = serde_json::to_string(&data).map_err(RssBackendError::from)?;
 
-        self.save(&new_config).await?;
+        let dir = file_handler::find_cache_dir()?;
+        fs::create_dir_all(&dir)
+            .await
+            .map_err(RssBackendError::WriteCache)?;
+        fs::write(dir.join(CACHE_NAME), content)
+            .await
+            .map_err(RssBackendError::WriteCache)?;
         Ok(())
     }
 
@@ -171,9 +458,42 @@ impl RssBackend {
         inner: Arc<Mutex<RssBackendInner>>,
         video_sender: Arc<BackendSender<Video>>,
         feed_sender: Arc<BackendSender<Feed>>,
+        video_probe: Option<Arc<VideoProbe>>,
+    ) -> Result<(), RssBackendError> {
+        let result = Self::fetch_feed_impl(
+            url,
+            inner.clone(),
+            video_sender.clone(),
+            feed_sender,
+            video_probe,
+        )
+        .await;
+
+        if let Err(ref error) = result {
+            let event = HookEvent::FetchError {
+                url: url.to_owned(),
+                error: error.to_string(),
+            };
+            let hooks = inner.lock().hooks.clone();
+            Self::run_hook(hooks, event, video_sender);
+        }
+        result
+    }
+
+    async fn fetch_feed_impl(
+        url: &str,
+        inner: Arc<Mutex<RssBackendInner>>,
+        video_sender: Arc<BackendSender<Video>>,
+        feed_sender: Arc<BackendSender<Feed>>,
+        video_probe: Option<Arc<VideoProbe>>,
     ) -> Result<(), RssBackendError> {
         let rss = Self::fetch_rss(url).await?;
-        Self::parse_videos(&rss, url, inner.clone(), video_sender.clone()).await?;
+        let urls = Self::parse_videos(&rss, url, inner.clone(), video_sender.clone()).await?;
+        Self::remove_stale_videos(url, &urls, &inner, &video_sender);
+
+        if let Some(video_probe) = video_probe {
+            Self::probe_videos(url, inner.clone(), video_sender.clone(), video_probe);
+        }
 
         let mut inner = inner.lock();
         let feed = Feed {
@@ -181,9 +501,22 @@ impl RssBackend {
             url: url.to_owned(),
         };
 
-        feed_sender.send(BackendMessage::New(feed.clone()));
-        if let Some(ref mut data) = inner.data {
-            data.feeds.push(feed);
+        // Feeds loaded from the cache are replaced when fetched again
+        let existing = inner
+            .data
+            .as_mut()
+            .and_then(|data| data.feeds.iter_mut().find(|existing| existing.url == url));
+        match existing {
+            Some(existing) => {
+                *existing = feed.clone();
+                feed_sender.send(BackendMessage::Update(feed));
+            }
+            None => {
+                if let Some(ref mut data) = inner.data {
+                    data.feeds.push(feed.clone());
+                }
+                feed_sender.send(BackendMessage::New(feed));
+            }
         }
 
         video_sender.send(BackendMessage::FinishedFetching);
@@ -197,31 +530,155 @@ impl RssBackend {
         feed_url: &str,
         inner: Arc<Mutex<RssBackendInner>>,
         video_sender: Arc<BackendSender<Video>>,
-    ) -> Result<(), RssBackendError> {
+    ) -> Result<Vec<String>, RssBackendError> {
         let author = rss.title().as_str();
-        rss.entries().iter().try_for_each(|entry| {
-            Self::parse_video(entry, author, feed_url, inner.clone(), video_sender.clone())
-        })
+        rss.entries()
+            .iter()
+            .map(|entry| {
+                Self::parse_video(entry, author, feed_url, inner.clone(), video_sender.clone())
+            })
+            .collect()
     }
 
-    fn parse_video(
-        entry: &Entry,
-        author: &str,
+    // Removes the videos of the feed that are no longer in it
+    fn remove_stale_videos(
+        feed_url: &str,
+        urls: &[String],
+        inner: &Mutex<RssBackendInner>,
+        video_sender: &BackendSender<Video>,
+    ) {
+        let mut inner = inner.lock();
+        if let Some(ref mut data) = inner.data {
+            data.videos.retain(|video| {
+                let keep = video.feed_url != feed_url || urls.contains(&video.url);
+                if !keep {
+                    video_sender.send(BackendMessage::Remove(video.clone()));
+                }
+                keep
+            });
+        }
+    }
+
+    fn probe_videos(
         feed_url: &str,
         inner: Arc<Mutex<RssBackendInner>>,
         video_sender: Arc<BackendSender<Video>>,
-    ) -> Result<(), RssBackendError> {
-        let description = entry
+        video_probe: Arc<VideoProbe>,
+    ) {
+        let videos: Vec<Video> = {
+            let inner = inner.lock();
+            inner
+                .data
+                .iter()
+                .flat_map(|data| data.videos.iter())
+                .filter(|video| {
+                    video.feed_url == feed_url && !inner.probes.contains_key(&video.url)
+                })
+                .cloned()
+                .collect()
+        };
+
+        for video in videos {
+            Self::probe_video(
+                video,
+                inner.clone(),
+                video_sender.clone(),
+                video_probe.clone(),
+            );
+        }
+    }
+
+    fn probe_video(
+        video: Video,
+        inner: Arc<Mutex<RssBackendInner>>,
+        video_sender: Arc<BackendSender<Video>>,
+        video_probe: Arc<VideoProbe>,
+    ) {
+        tokio::spawn(async move {
+            let Ok(_permit) = video_probe.permits.acquire().await else {
+                return;
+            };
+
+            let probe = match yt_dlp::probe(&video_probe.yt_dlp, &video.url).await {
+                Ok(probe) => probe,
+                Err(error) => {
+                    warn!("Failed to probe {}: {}", video.url, error);
+                    return;
+                }
+            };
+
+            let video = Self::apply_probe(video, &probe);
+            let mut inner = inner.lock();
+            inner.probes.insert(video.url.clone(), probe);
+            if let Some(ref mut data) = inner.data {
+                data.videos
+                    .iter_mut()
+                    .filter(|existing| existing.url == video.url)
+                    .for_each(|existing| *existing = video.clone());
+            }
+            video_sender.send(BackendMessage::Update(video));
+        });
+    }
+
+    fn apply_probe(video: Video, probe: &Probe) -> Video {
+        Video {
+            length: probe.duration.unwrap_or(video.length),
+            kind: probe.kind.unwrap_or(video.kind),
+            ..video
+        }
+    }
+
+    fn media_group(entry: &Entry) -> Option<&Extension> {
+        entry
             .extensions()
             .get("media")
             .and_then(|media| media.get("group"))
             .and_then(|group| group.first())
-            .and_then(|extension| extension.children().get("description"))
-            .and_then(|description| description.first())
+    }
+
+    fn media_child<'a>(extension: &'a Extension, name: &str) -> Option<&'a Extension> {
+        extension
+            .children()
+            .get(name)
+            .and_then(|children| children.first())
+    }
+
+    fn parse_video(
+        entry: &Entry,
+        author: &str,
+        feed_url: &str,
+        inner: Arc<Mutex<RssBackendInner>>,
+        video_sender: Arc<BackendSender<Video>>,
+    ) -> Result<String, RssBackendError> {
+        let media_group = Self::media_group(entry);
+        let media_child = |name| media_group.and_then(|group| Self::media_child(group, name));
+        let community = media_child("community");
+        let community_child = |name| community.and_then(|group| Self::media_child(group, name));
+
+        let description = media_child("description")
             .and_then(|description| description.value())
             .unwrap_or("")
             .to_string();
 
+        let thumbnail = media_child("thumbnail")
+            .and_then(|thumbnail| thumbnail.attrs().get("url"))
+            .cloned();
+
+        let views = community_child("statistics")
+            .and_then(|statistics| statistics.attrs().get("views"))
+            .and_then(|views| views.parse().ok());
+
+        let rating = community_child("starRating").and_then(|rating| {
+            Some(Rating {
+                average: rating.attrs().get("average")?.parse().ok()?,
+                count: rating.attrs().get("count")?.parse().ok()?,
+            })
+        });
+
+        let feed_length = media_child("content")
+            .and_then(|content| content.attrs().get("duration"))
+            .and_then(|duration| duration.parse().ok());
+
         let url = entry
             .links()
             .first()
@@ -234,49 +691,207 @@ impl RssBackend {
             .ok_or(RssBackendError::ParseVideo)?
             .to_owned();
 
+        let length = feed_length.unwrap_or(0);
+        // Feeds don't tell live streams and premieres apart from other videos, which is left to
+        // probing them
+        let kind = if url.contains("/shorts/") {
+            VideoKind::Short
+        } else {
+            VideoKind::Regular
+        };
+
         let video = Video {
             title: entry.title().to_string(),
             url,
             author: author.to_string(),
             feed_url: feed_url.to_string(),
             description,
-            length: 0,
+            length,
             date,
+            thumbnail,
+            views,
+            rating,
+            kind,
+        };
+        let video = match inner.lock().probes.get(&video.url) {
+            Some(probe) => Self::apply_probe(video, probe),
+            None => video,
         };
 
-        video_sender.send(BackendMessage::New(video.clone()));
-        if let Some(ref mut data) = inner.lock().data {
-            data.videos.push(video);
+        // Videos loaded from the cache are replaced when fetched again
+        let video_url = video.url.clone();
+        let mut inner = inner.lock();
+        let existing = inner.data.as_mut().and_then(|data| {
+            data.videos
+                .iter_mut()
+                .find(|existing| existing.url == video.url)
+        });
+        match existing {
+            Some(existing) => {
+                *existing = video.clone();
+                video_sender.send(BackendMessage::Update(video));
+            }
+            None => {
+                if let Some(ref mut data) = inner.data {
+                    data.videos.push(video.clone());
+                }
+                video_sender.send(BackendMessage::New(video));
+            }
         }
 
-        Ok(())
+        Ok(video_url)
     }
 
-    async fn save(&self, config: &RssConfig) -> Result<(), ConfigError> {
-        let file_handler = self.file_handler.lock().await;
-        file_handler.write(config).await
+    // Runs the hook in the background, reporting failures like fetch errors
+    fn run_hook(hooks: Hooks, event: HookEvent, video_sender: Arc<BackendSender<Video>>) {
+        tokio::spawn(async move {
+            if let Err(error) = hooks.run(event).await {
+                warn!("{}", error);
+                video_sender.send(BackendMessage::Error(error.to_string()));
+            }
+        });
+    }
+
+    fn run_feed_added_hook(&self, url: &str) {
+        let hooks = self.inner.lock().hooks.clone();
+        Self::run_hook(
+            hooks,
+            HookEvent::FeedAdded {
+                url: url.to_owned(),
+            },
+            self.video_sender.clone(),
+        );
+    }
+
+    // Saves the changed config and only uses it once it's been saved, which fails if rss.toml has
+    // changes that haven't been reloaded yet
+    async fn update_config(&self, update: impl FnOnce(&mut RssConfig)) -> Result<(), ConfigError> {
+        let mut file_handler = self.file_handler.lock().await;
+        let mut config = self.inner.lock().config.clone();
+        update(&mut config);
+
+        file_handler.write(&config).await?;
+        self.inner.lock().config = config;
+        Ok(())
     }
 
     fn fetch(&self) {
-        let mut inner = self.inner.lock();
-        if inner.data.is_none() {
-            inner.data = Some(Default::default());
+        let urls: Vec<String> = {
+            let mut inner = self.inner.lock();
+            if inner.data.is_none() {
+                inner.data = Some(Default::default());
+            }
+            inner
+                .config
+                .feeds
+                .iter()
+                .map(|feed| feed.url.clone())
+                .collect()
+        };
+
+        let handles: Vec<_> = urls
+            .into_iter()
+            .map(|url| (url.clone(), self.fetch_in_background(url)))
+            .collect();
+
+        let inner = self.inner.clone();
+        let video_sender = self.video_sender.clone();
+        tokio::spawn(async move {
+            let mut fetched = vec![];
+            for (url, handle) in handles {
+                if let Ok(true) = handle.await {
+                    fetched.push(url);
+                }
+            }
+
+            let result = Self::run_new_video_hooks(&fetched, inner, video_sender.clone()).await;
+            if let Err(error) = result {
+                video_sender.send(BackendMessage::Error(error.to_string()));
+            }
+        });
+    }
+
+    // Runs the new video hook for the videos of the fetched feeds that weren't in them when they
+    // were last fetched. Nothing is new in feeds that haven't been fetched with the hook before,
+    // and nothing is recorded without the hook, such as when listing videos from the command line.
+    async fn run_new_video_hooks(
+        fetched: &[String],
+        inner: Arc<Mutex<RssBackendInner>>,
+        video_sender: Arc<BackendSender<Video>>,
+    ) -> Result<(), ConfigError> {
+        let (hooks, feed_urls) = {
+            let inner = inner.lock();
+            let feed_urls: Vec<String> = inner
+                .config
+                .feeds
+                .iter()
+                .map(|feed| feed.url.clone())
+                .collect();
+            (inner.hooks.clone(), feed_urls)
+        };
+        if !hooks.has_new_video_hook() || fetched.is_empty() {
+            return Ok(());
         }
 
-        for url in inner.config.feeds.iter() {
-            let url = url.clone();
-            let inner = self.inner.clone();
-            let video_sender = self.video_sender.clone();
-            let feed_sender = self.feed_sender.clone();
-            tokio::spawn(async move {
-                match Self::fetch_feed(&url, inner, video_sender.clone(), feed_sender.clone()).await
-                {
-                    Ok(()) => (),
-                    Err(RssBackendError::ReadFeed { .. }) => (),
-                    Err(error) => video_sender.send(BackendMessage::Error(error.to_string())),
+        // Read on every fetch to pick up the videos seen by other instances in the meantime
+        let mut file_handler = ConfigFileHandler::from_state_file(SEEN_NAME).await?;
+        let mut seen: SeenVideos = file_handler.read().await?;
+        seen.feeds.retain(|url, _| feed_urls.contains(url));
+
+        let mut new_videos = vec![];
+        {
+            let inner = inner.lock();
+            for feed_url in fetched {
+                let videos: Vec<&Video> = inner
+                    .data
+                    .iter()
+                    .flat_map(|data| data.videos.iter())
+                    .filter(|video| video.feed_url == *feed_url)
+                    .collect();
+                let urls = videos.iter().map(|video| video.url.clone()).collect();
+                if let Some(seen_urls) = seen.feeds.insert(feed_url.clone(), urls) {
+                    new_videos.extend(
+                        videos
+                            .into_iter()
+                            .filter(|video| !seen_urls.contains(&video.url))
+                            .cloned(),
+                    );
                 }
-            });
+            }
+        }
+
+        file_handler.write(&seen).await?;
+        new_videos.sort_by_key(|video| video.date);
+        for video in new_videos {
+            let event = HookEvent::NewVideo { video };
+            Self::run_hook(hooks.clone(), event, video_sender.clone());
         }
+        Ok(())
+    }
+
+    // Fetches the feed in the background, the handle returns whether it was fetched
+    fn fetch_in_background(&self, url: String) -> JoinHandle<bool> {
+        let inner = self.inner.clone();
+        let video_sender = self.video_sender.clone();
+        let feed_sender = self.feed_sender.clone();
+        let video_probe = self.video_probe.lock().clone();
+        tokio::spawn(async move {
+            let fetch_result = Self::fetch_feed(
+                &url,
+                inner,
+                video_sender.clone(),
+                feed_sender.clone(),
+                video_probe,
+            )
+            .await;
+
+            match fetch_result {
+                Ok(()) => return true,
+                Err(RssBackendError::ReadFeed { .. }) => (),
+                Err(error) => video_sender.send(BackendMessage::Error(error.to_string())),
+            }
+            false
+        })
     }
 }
 
@@ -286,13 +901,19 @@ impl Backend for RssBackend {
         let mut file_handler = ConfigFileHandler::from_config_file(CONFIG_NAME).await?;
         let config = file_handler.read().await?;
 
-        let inner = RssBackendInner { config, data: None };
+        let inner = RssBackendInner {
+            config,
+            data: None,
+            probes: HashMap::new(),
+            hooks: Hooks::default(),
+        };
 
         Ok(Self {
             inner: Arc::new(Mutex::new(inner)),
             file_handler: tokio::sync::Mutex::new(file_handler),
             video_sender: Arc::new(BackendSender::new()),
             feed_sender: Arc::new(BackendSender::new()),
+            video_probe: Mutex::new(None),
         })
     }
 
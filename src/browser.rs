@@ -0,0 +1,50 @@
+use std::{io, process::Stdio};
+use thiserror::Error;
+use tokio::process::Command;
+
+#[derive(Debug, Error)]
+pub enum BrowserError {
+    #[error("Failed to open browser: {}", _0)]
+    Open(#[from] io::Error),
+
+    #[error("Browser exited with {}", _0)]
+    Status(std::process::ExitStatus),
+}
+
+// Opens urls with the configured browser command, or xdg-open if there is none
+#[derive(Clone)]
+pub struct Browser {
+    command: Option<String>,
+}
+
+impl Browser {
+    pub fn new(command: Option<String>) -> Self {
+        Self { command }
+    }
+
+    pub async fn open(&self, url: &str) -> Result<(), BrowserError> {
+        let mut command = match self.command {
+            // The url is passed as an argument to avoid having to quote it
+            Some(ref command) => {
+                let mut shell = Command::new("sh");
+                shell.arg("-c").arg(format!("{command} \"$1\"")).arg("sh");
+                shell
+            }
+            None => Command::new("xdg-open"),
+        };
+
+        let status = command
+            .arg(url)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await?;
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(BrowserError::Status(status))
+        }
+    }
+}
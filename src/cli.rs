@@ -0,0 +1,515 @@
+use crate::{
+    backend::{Backend, BackendError, Video, rss::RssBackend, single_line},
+    config::ConfigHandler,
+    config_error::ConfigError,
+    download::DownloadManager,
+    file_handler, notification,
+    playback::PlaybackHandler,
+    playlist::{self, PlaylistError},
+    remote::{self, RemoteCommand, RemoteError},
+};
+
+use chrono::{Duration, Utc};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use std::{
+    cmp::Reverse,
+    collections::HashSet,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CliError {
+    #[error("{}", _0)]
+    Backend(#[from] BackendError),
+
+    #[error("{}", _0)]
+    Config(#[from] ConfigError),
+
+    #[error("{}", _0)]
+    Playlist(#[from] PlaylistError),
+
+    #[error("Not subscribed to {}", _0)]
+    UnknownFeed(String),
+
+    #[error("Failed to serialize videos: {}", _0)]
+    Serialize(#[from] serde_json::Error),
+
+    #[error("Failed to send notification: {}", _0)]
+    Notify(#[source] std::io::Error),
+
+    #[error("{}", _0)]
+    Remote(#[from] RemoteError),
+}
+
+// New videos are summarized in a single notification when there are more than this
+const MAX_NOTIFICATIONS: usize = 5;
+
+/// Terminal interface for YouTube and other RSS feeds
+#[derive(Parser)]
+#[command(version)]
+pub struct Cli {
+    /// Player profile or command template, overriding the ones of the config and feeds
+    #[arg(long)]
+    pub player: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Add, remove or list subscribed feeds
+    Feeds {
+        #[command(subcommand)]
+        command: FeedsCommand,
+    },
+
+    /// Import the subscriptions csv of a YouTube takeout
+    Import { path: PathBuf },
+
+    /// Export the unwatched videos to an M3U8 or XSPF playlist
+    Export {
+        path: PathBuf,
+
+        /// Reference downloaded videos by their local files
+        #[arg(long)]
+        local_files: bool,
+    },
+
+    /// Fetch all feeds and print their videos, newest first
+    Fetch,
+
+    /// Fetch all feeds and print the matching videos, newest first. Exits with 1 if there are none.
+    List {
+        /// Only list videos published since the last played video
+        #[arg(long)]
+        new: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = ListFormat::Plain)]
+        format: ListFormat,
+
+        /// Only list videos of feeds with the url or a title containing the text
+        #[arg(long)]
+        feed: Vec<String>,
+
+        /// Only list videos of feeds with the tag
+        #[arg(long)]
+        tag: Vec<String>,
+
+        /// Only list videos published within the age, such as 30m, 12h, 3d or 2w
+        #[arg(long, value_parser = parse_duration)]
+        max_age: Option<Duration>,
+
+        /// List at most this many videos
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+
+    /// Fetch the feeds on an interval to keep the cache up to date and notify about new videos
+    Daemon {
+        /// Time between fetches, such as 30m or 2h
+        #[arg(long, default_value = "30m", value_parser = parse_duration)]
+        interval: Duration,
+    },
+
+    /// Send a command to the running instance of tuibe
+    Remote {
+        #[command(subcommand)]
+        command: RemoteCommand,
+    },
+
+    /// List or restore the backups kept of the config files
+    Backups {
+        #[command(subcommand)]
+        command: BackupsCommand,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BackupsCommand {
+    /// List the backups, from newest to oldest
+    List,
+
+    /// Replace a file with one of its backups, for example `tuibe backups restore rss`
+    Restore {
+        /// Name of the file, such as rss or config
+        name: String,
+
+        /// Backup to restore, where 1 is the newest
+        #[arg(default_value_t = 1)]
+        version: usize,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ListFormat {
+    Plain,
+    Tsv,
+    Json,
+}
+
+#[derive(Serialize)]
+struct ListedVideo<'a> {
+    title: &'a str,
+    author: &'a str,
+    url: &'a str,
+    feed_url: &'a str,
+    date: String,
+    // Length in seconds, 0 if unknown
+    length: u32,
+}
+
+#[derive(Subcommand)]
+pub enum FeedsCommand {
+    /// Subscribe to a feed
+    Add { url: String },
+
+    /// Unsubscribe from a feed
+    Remove { url: String },
+
+    /// List the subscribed feeds
+    List,
+}
+
+pub async fn run(command: Command) -> Result<ExitCode, CliError> {
+    match command {
+        Command::Feeds { command } => feeds(command).await?,
+        Command::Import { path } => import_youtube_takeout(&path).await?,
+        Command::Export { path, local_files } => export_playlist(&path, local_files).await?,
+        Command::Fetch => fetch().await?,
+        Command::List {
+            new,
+            format,
+            feed,
+            tag,
+            max_age,
+            limit,
+        } => {
+            let filter = ListFilter {
+                new,
+                feeds: feed,
+                tags: tag,
+                max_age,
+            };
+            return list(filter, format, limit).await;
+        }
+        Command::Daemon { interval } => daemon(interval).await?,
+        Command::Remote { command } => remote::send(&command).await?,
+        Command::Backups { command } => backups(command).await?,
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+async fn feeds(command: FeedsCommand) -> Result<(), CliError> {
+    let backend = RssBackend::load().await?;
+    let is_subscribed = |url: &str| backend.feed_configs().iter().any(|feed| feed.url == url);
+
+    match command {
+        FeedsCommand::Add { url } => {
+            if is_subscribed(&url) {
+                println!("Already subscribed to {url}.");
+            } else {
+                backend.add_feed(&url).await?;
+                println!("Subscribed to {url}.");
+            }
+        }
+        FeedsCommand::Remove { url } => {
+            if !is_subscribed(&url) {
+                return Err(CliError::UnknownFeed(url));
+            }
+            backend.remove_feed(&url).await?;
+            println!("Unsubscribed from {url}.");
+        }
+        FeedsCommand::List => {
+            for feed in backend.feed_configs() {
+                match feed.player {
+                    Some(player) => println!("{} (player: {player})", feed.url),
+                    None => println!("{}", feed.url),
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn backups(command: BackupsCommand) -> Result<(), CliError> {
+    match command {
+        BackupsCommand::List => {
+            for backup in file_handler::list_backups().await? {
+                let modified = backup.modified.map_or_else(String::new, |modified| {
+                    modified.format("%Y-%m-%d %H:%M:%S").to_string()
+                });
+                println!("{}\t{}\t{modified}", backup.name, backup.version);
+            }
+        }
+        BackupsCommand::Restore { name, version } => {
+            let path = file_handler::restore_backup(&name, version).await?;
+            println!("Restored {} from backup {version}.", path.display());
+        }
+    }
+
+    Ok(())
+}
+
+async fn import_youtube_takeout(path: &Path) -> Result<(), CliError> {
+    println!("Importing subscriptions...");
+    RssBackend::load()
+        .await?
+        .import_youtube(&path.to_string_lossy())
+        .await?;
+    println!("Done.");
+    Ok(())
+}
+
+async fn export_playlist(path: &Path, local_files: bool) -> Result<(), CliError> {
+    println!("Fetching feeds...");
+    let config = ConfigHandler::load().await?;
+    let downloads = DownloadManager::load(config.download_options()).await?;
+    let playback = PlaybackHandler::load().await?;
+    let hidden_kinds = config.hidden_kinds();
+    let mut videos: Vec<_> = fetch_videos(&RssBackend::load().await?)
+        .await?
+        .into_iter()
+        .filter(|video| {
+            video.date.timestamp() > config.last_played_timestamp()
+                && !playback.is_watched(&video.url)
+                && !hidden_kinds.contains(&video.kind)
+        })
+        .collect();
+    videos.sort_by_key(|video| video.date);
+
+    let local_path = |url: &str| local_files.then(|| downloads.local_path(url)).flatten();
+    playlist::export(path, &videos, local_path).await?;
+    println!("Exported {} videos.", videos.len());
+    Ok(())
+}
+
+// Fetches the videos of every feed, printing the errors of the feeds that failed to fetch
+async fn fetch_videos(backend: &RssBackend) -> Result<Vec<Video>, CliError> {
+    let fetched = backend.fetch_videos().await?;
+    for error in fetched.errors {
+        eprintln!("Failed to fetch {error}");
+    }
+    Ok(fetched.videos)
+}
+
+async fn fetch() -> Result<(), CliError> {
+    let mut videos = fetch_videos(&RssBackend::load().await?).await?;
+    videos.sort_by_key(|video| Reverse(video.date));
+
+    for video in videos {
+        println!(
+            "{}\t{}\t{}\t{}",
+            video.date.format("%Y-%m-%d %H:%M"),
+            video.author,
+            video.title,
+            video.url
+        );
+    }
+    Ok(())
+}
+
+struct ListFilter {
+    new: bool,
+    feeds: Vec<String>,
+    tags: Vec<String>,
+    max_age: Option<Duration>,
+}
+
+async fn list(
+    filter: ListFilter,
+    format: ListFormat,
+    limit: Option<usize>,
+) -> Result<ExitCode, CliError> {
+    let config = ConfigHandler::load().await?;
+    let backend = RssBackend::load().await?;
+    let playback = PlaybackHandler::load().await?;
+    let hidden_kinds = config.hidden_kinds();
+    let last_played_timestamp = config.last_played_timestamp();
+    let oldest = filter.max_age.map(|max_age| Utc::now() - max_age);
+
+    // Feeds matching both the feed and tag filters
+    let feed_urls: Vec<String> = backend
+        .feed_configs()
+        .into_iter()
+        .filter(|feed| {
+            filter.tags.is_empty() || filter.tags.iter().any(|tag| feed.tags.contains(tag))
+        })
+        .map(|feed| feed.url)
+        .collect();
+    let matches_feed = |video: &Video| {
+        let title = video.author.to_lowercase();
+        feed_urls.contains(&video.feed_url)
+            && (filter.feeds.is_empty()
+                || filter
+                    .feeds
+                    .iter()
+                    .any(|feed| *feed == video.feed_url || title.contains(&feed.to_lowercase())))
+    };
+
+    let mut videos: Vec<Video> = fetch_videos(&backend)
+        .await?
+        .into_iter()
+        .filter(|video| {
+            !hidden_kinds.contains(&video.kind)
+                && (!filter.new
+                    || (video.date.timestamp() > last_played_timestamp
+                        && !playback.is_watched(&video.url)))
+                && oldest.is_none_or(|oldest| video.date > oldest)
+                && matches_feed(video)
+        })
+        .collect();
+    videos.sort_by_key(|video| Reverse(video.date));
+    videos.truncate(limit.unwrap_or(videos.len()));
+
+    match format {
+        ListFormat::Plain => {
+            for video in &videos {
+                println!(
+                    "{}  {} - {}  {}",
+                    video.date.format("%Y-%m-%d %H:%M"),
+                    video.author,
+                    video.title,
+                    video.url
+                );
+            }
+        }
+        ListFormat::Tsv => {
+            for video in &videos {
+                println!(
+                    "{}\t{}\t{}\t{}\t{}",
+                    video.date.to_rfc3339(),
+                    single_line(&video.author),
+                    single_line(&video.title),
+                    video.url,
+                    video.length
+                );
+            }
+        }
+        ListFormat::Json => {
+            let listed: Vec<ListedVideo> = videos
+                .iter()
+                .map(|video| ListedVideo {
+                    title: &video.title,
+                    author: &video.author,
+                    url: &video.url,
+                    feed_url: &video.feed_url,
+                    date: video.date.to_rfc3339(),
+                    length: video.length,
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&listed)?);
+        }
+    }
+
+    // Lets scripts check whether there's anything to watch
+    Ok(if videos.is_empty() {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+async fn daemon(interval: Duration) -> Result<(), CliError> {
+    // Videos in the cache have already been seen, and nothing is new on the first fetch without one
+    let mut known: Option<HashSet<String>> = RssBackend::load()
+        .await?
+        .load_cache()
+        .await?
+        .map(|videos| videos.into_iter().map(|video| video.url).collect());
+
+    loop {
+        if let Err(error) = daemon_fetch(&mut known).await {
+            eprintln!("Error: {error}");
+        }
+        tokio::time::sleep(interval.to_std().unwrap_or_default()).await;
+    }
+}
+
+async fn daemon_fetch(known: &mut Option<HashSet<String>>) -> Result<(), CliError> {
+    // Loaded on every fetch to pick up changes made in the meantime
+    let config = ConfigHandler::load().await?;
+    let backend = RssBackend::load().await?.with_hooks(config.hooks());
+    // Feeds that fail to fetch keep their cached videos
+    backend.load_cache().await?;
+    let videos = fetch_videos(&backend).await?;
+    backend.save_cache().await?;
+
+    // Only the videos that are still in the cache need to be remembered
+    let urls: HashSet<String> = videos.iter().map(|video| video.url.clone()).collect();
+    let Some(known) = known.replace(urls) else {
+        return Ok(());
+    };
+
+    let hidden_kinds = config.hidden_kinds();
+    let notify_feeds: Vec<String> = backend
+        .feed_configs()
+        .into_iter()
+        .filter(|feed| feed.notify)
+        .map(|feed| feed.url)
+        .collect();
+    let mut new_videos: Vec<Video> = videos
+        .into_iter()
+        .filter(|video| !known.contains(&video.url))
+        .filter(|video| {
+            notify_feeds.contains(&video.feed_url)
+                && !hidden_kinds.contains(&video.kind)
+                && video.date.timestamp() > config.last_played_timestamp()
+        })
+        .collect();
+    new_videos.sort_by_key(|video| video.date);
+
+    for video in &new_videos {
+        println!(
+            "New video: {} - {} {}",
+            video.author, video.title, video.url
+        );
+    }
+
+    if new_videos.len() > MAX_NOTIFICATIONS {
+        let mut authors: Vec<&str> = new_videos
+            .iter()
+            .map(|video| video.author.as_str())
+            .collect();
+        authors.sort_unstable();
+        authors.dedup();
+        let summary = format!("{} new videos", new_videos.len());
+        notification::notify(&summary, &authors.join(", "))
+            .await
+            .map_err(CliError::Notify)?;
+    } else {
+        for video in &new_videos {
+            notification::notify(&video.author, &video.title)
+                .await
+                .map_err(CliError::Notify)?;
+        }
+    }
+
+    Ok(())
+}
+
+// Parses durations such as 30m, 12h, 3d and 2w
+fn parse_duration(duration: &str) -> Result<Duration, String> {
+    let invalid = || {
+        format!("invalid duration {duration}, expected a positive number followed by m, h, d or w")
+    };
+    let split = duration.len().checked_sub(1).ok_or_else(invalid)?;
+    let (amount, unit) = duration.split_at_checked(split).ok_or_else(invalid)?;
+    let amount = match amount.parse::<u32>() {
+        Ok(0) | Err(_) => return Err(invalid()),
+        Ok(amount) => i64::from(amount),
+    };
+    match unit {
+        "m" => Ok(Duration::minutes(amount)),
+        "h" => Ok(Duration::hours(amount)),
+        "d" => Ok(Duration::days(amount)),
+        "w" => Ok(Duration::weeks(amount)),
+        _ => Err(invalid()),
+    }
+}
@@ -0,0 +1,152 @@
+use crate::backend::Video;
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use std::{env, io, process::Stdio};
+use thiserror::Error;
+use tokio::{io::AsyncWriteExt, process::Command};
+use wl_clipboard_rs::copy::{MimeType, Options, Source};
+
+// X11 clipboard tools, tried in order
+const X11_COMMANDS: [&[&str]; 2] = [
+    &["xclip", "-selection", "clipboard"],
+    &["xsel", "--clipboard", "--input"],
+];
+
+#[derive(Debug, Error)]
+pub enum ClipboardError {
+    #[error("Failed to copy to clipboard: {}", _0)]
+    Copy(String),
+}
+
+// Copies text through the first clipboard that's available: the configured command, Wayland, X11
+// and lastly the terminal through OSC 52, which can't tell whether it worked. The OSC 52 sequence is
+// passed to a writer since it has to be written between frames.
+#[derive(Clone)]
+pub struct Clipboard {
+    command: Option<String>,
+    osc52: bool,
+    // Format of copied videos, see `format_video`
+    format: String,
+}
+
+impl Clipboard {
+    pub fn new(command: Option<String>, osc52: bool, format: String) -> Self {
+        Self {
+            command,
+            osc52,
+            format,
+        }
+    }
+
+    // Formats the videos in the configured format, one per line
+    pub fn format_videos(&self, videos: &[Video]) -> String {
+        let lines: Vec<String> = videos
+            .iter()
+            .map(|video| format_video(&self.format, video))
+            .collect();
+        lines.join("\n")
+    }
+
+    pub async fn copy(
+        &self,
+        text: &str,
+        write_osc52: impl FnOnce(String),
+    ) -> Result<(), ClipboardError> {
+        let mut errors = vec![];
+
+        if let Some(ref command) = self.command {
+            match run("sh", &["-c", command], text).await {
+                Ok(()) => return Ok(()),
+                Err(error) => errors.push(format!("{command}: {error}")),
+            }
+        }
+
+        if env::var_os("WAYLAND_DISPLAY").is_some() {
+            match copy_wayland(text.to_owned()).await {
+                Ok(()) => return Ok(()),
+                Err(error) => errors.push(format!("wayland: {error}")),
+            }
+        }
+
+        if env::var_os("DISPLAY").is_some() {
+            for command in X11_COMMANDS {
+                match run(command[0], &command[1..], text).await {
+                    Ok(()) => return Ok(()),
+                    Err(error) => errors.push(format!("{}: {error}", command[0])),
+                }
+            }
+        }
+
+        // Lets the terminal set the clipboard, which also works over SSH and in tmux with
+        // set-clipboard
+        if self.osc52 {
+            write_osc52(format!("\x1b]52;c;{}\x07", BASE64.encode(text)));
+            return Ok(());
+        }
+
+        if errors.is_empty() {
+            errors.push(String::from("no clipboard available"));
+        }
+        Err(ClipboardError::Copy(errors.join(", ")))
+    }
+}
+
+// Formats the video with one of the formats "url", "full" and "markdown", or with a template such
+// as "{title} ({url})". The supported placeholders are {url}, {title}, {author} and {date}.
+fn format_video(format: &str, video: &Video) -> String {
+    let template = match format {
+        "url" => "{url}",
+        "full" => "{title} — {author} — {url}",
+        "markdown" => "[{title}]({url})",
+        template => template,
+    };
+
+    let title = if format == "markdown" {
+        video.title.replace('[', "\\[").replace(']', "\\]")
+    } else {
+        video.title.clone()
+    };
+
+    template
+        .replace("{url}", &video.url)
+        .replace("{title}", &title)
+        .replace("{author}", &video.author)
+        .replace("{date}", &video.date.format("%Y-%m-%d").to_string())
+}
+
+// The Wayland clipboard blocks while talking to the compositor
+async fn copy_wayland(text: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        Options::new().copy(
+            Source::Bytes(text.into_bytes().into()),
+            MimeType::Autodetect,
+        )
+    })
+    .await
+    .map_err(|error| error.to_string())?
+    .map_err(|error| error.to_string())
+}
+
+// Runs the command with the text as input
+async fn run(program: &str, args: &[&str], text: &str) -> io::Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    // The child is waited on even if writing fails, stdin is closed first so that it can exit
+    let written = match child.stdin.take() {
+        Some(mut stdin) => stdin.write_all(text.as_bytes()).await,
+        None => Ok(()),
+    };
+    let status = child.wait().await?;
+    written?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(status.to_string()))
+    }
+}
@@ -1,22 +1,121 @@
 use super::file_handler::ConfigFileHandler;
-use crate::config_error::ConfigError;
+use crate::{
+    backend::VideoKind,
+    browser::Browser,
+    clipboard::Clipboard,
+    config_error::ConfigError,
+    download::DownloadOptions,
+    hooks::{Hooks, HooksConfig},
+};
 
 use chrono::Utc;
+use log::warn;
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, env, path::PathBuf};
 
 const CONFIG_NAME: &str = "config";
+const STATE_NAME: &str = "state";
 
 #[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
+    // Default player command template, see `player::command`
     pub player: String,
-    pub last_played_timestamp: i64,
+    // Named player command templates
+    pub players: BTreeMap<String, String>,
+    // Only read to move it to the state file, where it's been kept since
+    #[serde(skip_serializing)]
+    pub last_played_timestamp: Option<i64>,
+    pub yt_dlp: String,
+    #[serde(alias = "probe_durations")]
+    pub probe_videos: bool,
+    pub hide_shorts: bool,
+    pub hide_upcoming: bool,
+    pub thumbnails: ThumbnailMode,
+    // Track playback through mpv IPC to resume videos and mark them as watched
+    pub mpv_ipc: bool,
+    // Percentage of a video that needs to be played for it to count as watched
+    pub watched_threshold: u8,
+    // Append videos to the running mpv instance instead of starting a new one
+    pub queue: bool,
+    // yt-dlp format and output template of downloaded videos
+    pub download_format: Option<String>,
+    pub download_output: String,
+    pub max_downloads: usize,
+    // Directory exported playlists are written to, the working directory if empty
+    pub playlist_dir: String,
+    // Reference downloaded videos by their local files in exported playlists
+    pub playlist_local_files: bool,
+    // Copy through the terminal with OSC 52 when there's no Wayland or X11 clipboard
+    pub osc52: bool,
+    // Command that copied text is piped to, which takes precedence over the other clipboards
+    pub clipboard_command: Option<String>,
+    // Command that opened pages are passed to, xdg-open if unset
+    pub browser: Option<String>,
+    // Format of videos copied with `Y`: "url", "full", "markdown" or a template
+    pub copy_format: String,
+    pub hooks: HooksConfig,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThumbnailMode {
+    #[default]
+    Auto,
+    Kitty,
+    Sixel,
+    HalfBlocks,
+    Off,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             player: String::from("mpv"),
+            players: BTreeMap::new(),
+            last_played_timestamp: None,
+            yt_dlp: String::from("yt-dlp"),
+            probe_videos: false,
+            hide_shorts: false,
+            hide_upcoming: false,
+            thumbnails: ThumbnailMode::default(),
+            mpv_ipc: false,
+            watched_threshold: 90,
+            queue: false,
+            download_format: None,
+            download_output: String::from("~/Videos/tuibe/%(title)s [%(id)s].%(ext)s"),
+            max_downloads: 2,
+            playlist_dir: String::new(),
+            playlist_local_files: false,
+            osc52: true,
+            clipboard_command: None,
+            copy_format: String::from("url"),
+            browser: None,
+            hooks: HooksConfig::default(),
+        }
+    }
+}
+
+impl Config {
+    fn validate(&self) -> Result<(), ConfigError> {
+        if self.watched_threshold > 100 {
+            return Err(ConfigError::InvalidWatchedThreshold(self.watched_threshold));
+        }
+        Ok(())
+    }
+}
+
+// Runtime state which is kept in the state dir, separate from the config
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct State {
+    pub last_played_timestamp: i64,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
             last_played_timestamp: Utc::now().timestamp(),
         }
     }
@@ -24,39 +123,216 @@ impl Default for Config {
 
 pub struct ConfigHandler {
     pub config: Mutex<Config>,
+    state: Mutex<State>,
+    // Player that takes precedence over the ones of feeds and the config
+    player_override: Option<String>,
     file_handler: tokio::sync::Mutex<ConfigFileHandler<Config>>,
+    state_file_handler: tokio::sync::Mutex<ConfigFileHandler<State>>,
+}
+
+// The timestamp used to be kept in the config. It's moved to the state file when there's none yet,
+// and removed from the config so that it isn't taken again if the state file is removed later.
+async fn read_state(
+    config: &mut Config,
+    file_handler: &mut ConfigFileHandler<Config>,
+    state_file_handler: &mut ConfigFileHandler<State>,
+) -> Result<State, ConfigError> {
+    let Some(last_played_timestamp) = config.last_played_timestamp.take() else {
+        return state_file_handler.read().await;
+    };
+
+    let state = state_file_handler
+        .read_or_else(|| State {
+            last_played_timestamp,
+        })
+        .await?;
+    if let Err(error) = file_handler.write(config).await {
+        warn!(
+            "Failed to remove last_played_timestamp from the config: {}",
+            error
+        );
+    }
+    Ok(state)
 }
 
 impl ConfigHandler {
     pub async fn load() -> Result<Self, ConfigError> {
         let mut file_handler = ConfigFileHandler::from_config_file(CONFIG_NAME).await?;
-        let config = file_handler.read().await?;
+        let mut config: Config = file_handler.read().await?;
+        config.validate()?;
+
+        let mut state_file_handler = ConfigFileHandler::from_state_file(STATE_NAME).await?;
+        let state = read_state(&mut config, &mut file_handler, &mut state_file_handler).await?;
 
         Ok(Self {
             config: Mutex::new(config),
+            state: Mutex::new(state),
+            player_override: None,
             file_handler: tokio::sync::Mutex::new(file_handler),
+            state_file_handler: tokio::sync::Mutex::new(state_file_handler),
         })
     }
 
+    // Reads the config again if it's been changed, returns whether it was
+    pub async fn reload(&self) -> Result<bool, ConfigError> {
+        let mut file_handler = self.file_handler.lock().await;
+        let Some(config) = file_handler.reload().await? else {
+            return Ok(false);
+        };
+
+        config.validate()?;
+        *self.config.lock() = config;
+        Ok(true)
+    }
+
     pub async fn set_last_played_timestamp(
         &self,
         last_played_timestamp: i64,
     ) -> Result<(), ConfigError> {
-        let new_config = {
-            let mut config = self.config.lock();
-            config.last_played_timestamp = last_played_timestamp;
-            config.clone()
+        let new_state = {
+            let mut state = self.state.lock();
+            state.last_played_timestamp = last_played_timestamp;
+            state.clone()
         };
 
-        let file_handler = self.file_handler.lock().await;
-        file_handler.write(&new_config).await
+        let mut state_file_handler = self.state_file_handler.lock().await;
+        state_file_handler.write(&new_state).await
+    }
+
+    pub fn with_player_override(mut self, player: Option<String>) -> Self {
+        self.player_override = player;
+        self
+    }
+
+    pub fn player_override(&self) -> Option<String> {
+        self.player_override.clone()
     }
 
     pub fn player(&self) -> String {
         self.config.lock().player.clone()
     }
 
+    // Returns the template of the profile with the provided name. Names that don't match a
+    // profile are used as templates themselves.
+    pub fn player_template(&self, name: &str) -> String {
+        self.config
+            .lock()
+            .players
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| name.to_owned())
+    }
+
+    pub fn player_profiles(&self) -> Vec<String> {
+        self.config.lock().players.keys().cloned().collect()
+    }
+
+    // The yt-dlp command to probe videos with, if enabled
+    pub fn video_probe(&self) -> Option<String> {
+        let config = self.config.lock();
+        config.probe_videos.then(|| config.yt_dlp.clone())
+    }
+
+    pub fn hidden_kinds(&self) -> Vec<VideoKind> {
+        let config = self.config.lock();
+        let mut hidden_kinds = vec![];
+        if config.hide_shorts {
+            hidden_kinds.push(VideoKind::Short);
+        }
+        if config.hide_upcoming {
+            hidden_kinds.push(VideoKind::Upcoming);
+        }
+        hidden_kinds
+    }
+
+    pub fn thumbnails(&self) -> ThumbnailMode {
+        self.config.lock().thumbnails
+    }
+
+    // The watched threshold percentage if playback tracking is enabled
+    pub fn playback_tracking(&self) -> Option<u8> {
+        let config = self.config.lock();
+        config.mpv_ipc.then_some(config.watched_threshold)
+    }
+
+    pub fn queue(&self) -> bool {
+        self.config.lock().queue
+    }
+
+    pub fn download_options(&self) -> DownloadOptions {
+        let config = self.config.lock();
+        DownloadOptions {
+            yt_dlp: config.yt_dlp.clone(),
+            format: config.download_format.clone(),
+            output: config.download_output.clone(),
+            max_concurrent: config.max_downloads,
+        }
+    }
+
+    pub fn playlist_dir(&self) -> PathBuf {
+        let playlist_dir = self.config.lock().playlist_dir.clone();
+        match (playlist_dir.strip_prefix("~/"), env::var("HOME")) {
+            (Some(relative), Ok(home)) => PathBuf::from(home).join(relative),
+            _ => PathBuf::from(playlist_dir),
+        }
+    }
+
+    pub fn playlist_local_files(&self) -> bool {
+        self.config.lock().playlist_local_files
+    }
+
+    pub fn browser(&self) -> Browser {
+        Browser::new(self.config.lock().browser.clone())
+    }
+
+    pub fn hooks(&self) -> Hooks {
+        Hooks::new(self.config.lock().hooks.clone())
+    }
+
+    pub fn clipboard(&self) -> Clipboard {
+        let config = self.config.lock();
+        Clipboard::new(
+            config.clipboard_command.clone(),
+            config.osc52,
+            config.copy_format.clone(),
+        )
+    }
+
     pub fn last_played_timestamp(&self) -> i64 {
-        self.config.lock().last_played_timestamp
+        self.state.lock().last_played_timestamp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::path::Path;
+
+    async fn read_state_in(dir: &Path) -> State {
+        let mut file_handler = ConfigFileHandler::with_path(dir.join("config.toml"), true);
+        let mut config: Config = file_handler.read().await.unwrap();
+        let mut state_file_handler = ConfigFileHandler::with_path(dir.join("state.toml"), false);
+        read_state(&mut config, &mut file_handler, &mut state_file_handler)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn moves_last_played_timestamp_to_existing_state_dir() {
+        let dir = std::env::temp_dir().join(format!("tuibe-test-state-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("config.toml"), "last_played_timestamp = 123\n").unwrap();
+
+        assert_eq!(read_state_in(&dir).await.last_played_timestamp, 123);
+        let config = std::fs::read_to_string(dir.join("config.toml")).unwrap();
+        assert!(!config.contains("last_played_timestamp"));
+        assert_eq!(read_state_in(&dir).await.last_played_timestamp, 123);
+
+        // The timestamp isn't taken from the config again once the state file is gone
+        std::fs::remove_file(dir.join("state.toml")).unwrap();
+        assert_ne!(read_state_in(&dir).await.last_played_timestamp, 123);
+
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 }
@@ -22,4 +22,22 @@ pub enum ConfigError {
 
     #[error("Failed to write to config file")]
     WriteConfigFile(#[source] std::io::Error),
+
+    #[error("Failed to reload {}: {}", _0.display(), _1)]
+    InvalidConfigFile(std::path::PathBuf, #[source] toml::de::Error),
+
+    #[error("{} was changed by another program, not overwriting it", _0.display())]
+    Conflict(std::path::PathBuf),
+
+    #[error("watched_threshold is a percentage and can't be {}", _0)]
+    InvalidWatchedThreshold(u8),
+
+    #[error("Failed to move state file from the config directory: {}", _0)]
+    MigrateStateFile(#[source] std::io::Error),
+
+    #[error("Failed to back up config file: {}", _0)]
+    Backup(#[source] std::io::Error),
+
+    #[error("There is no backup {} of {}", _1, _0)]
+    MissingBackup(String, usize),
 }
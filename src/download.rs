@@ -0,0 +1,241 @@
+use super::file_handler::ConfigFileHandler;
+use crate::{
+    backend::{
+        Video,
+        channel::{BackendMessage, BackendReceiver, BackendSender},
+    },
+    config_error::ConfigError,
+    yt_dlp::{self, DownloadProgress, YtDlpError},
+};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+use tokio::{
+    select,
+    sync::{Notify, Semaphore},
+};
+
+const DOWNLOADS_NAME: &str = "downloads";
+
+#[derive(Clone)]
+pub struct DownloadOptions {
+    pub yt_dlp: String,
+    pub format: Option<String>,
+    // yt-dlp output template
+    pub output: String,
+    pub max_concurrent: usize,
+}
+
+// Finished downloads, which are kept to play the videos from the local files
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Downloaded {
+    pub videos: Vec<DownloadedVideo>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DownloadedVideo {
+    pub path: PathBuf,
+    pub video: Video,
+}
+
+#[derive(Clone)]
+pub enum DownloadState {
+    Queued,
+    Downloading(DownloadProgress),
+    Finished(PathBuf),
+    Failed(String),
+    Cancelled,
+}
+
+#[derive(Clone)]
+pub struct Download {
+    // Downloads are listed in the order they were first requested
+    pub id: usize,
+    pub video: Video,
+    pub state: DownloadState,
+}
+
+impl Download {
+    pub fn is_active(&self) -> bool {
+        matches!(
+            self.state,
+            DownloadState::Queued | DownloadState::Downloading(_)
+        )
+    }
+}
+
+pub struct DownloadManager {
+    options: Mutex<DownloadOptions>,
+    downloads: Mutex<Vec<Download>>,
+    cancels: Mutex<HashMap<String, Arc<Notify>>>,
+    // Has a permit for every download that can run at the same time
+    semaphore: Arc<Semaphore>,
+    downloaded: Mutex<Downloaded>,
+    file_handler: tokio::sync::Mutex<ConfigFileHandler<Downloaded>>,
+    sender: BackendSender<Download>,
+}
+
+impl DownloadManager {
+    pub async fn load(options: DownloadOptions) -> Result<Self, ConfigError> {
+        let mut file_handler = ConfigFileHandler::from_state_file(DOWNLOADS_NAME).await?;
+        let downloaded: Downloaded = file_handler.read().await?;
+        let downloads = downloaded
+            .videos
+            .iter()
+            .enumerate()
+            .map(|(id, downloaded)| Download {
+                id,
+                video: downloaded.video.clone(),
+                state: DownloadState::Finished(downloaded.path.clone()),
+            })
+            .collect();
+
+        Ok(Self {
+            semaphore: Arc::new(Semaphore::new(options.max_concurrent.max(1))),
+            options: Mutex::new(options),
+            downloads: Mutex::new(downloads),
+            cancels: Mutex::new(HashMap::new()),
+            downloaded: Mutex::new(downloaded),
+            file_handler: tokio::sync::Mutex::new(file_handler),
+            sender: BackendSender::new(),
+        })
+    }
+
+    // Used for the downloads that start from then on. Lowering the number of concurrent downloads
+    // takes effect once enough of the running ones have finished.
+    pub fn set_options(&self, options: DownloadOptions) {
+        let mut current = self.options.lock();
+        let (old, new) = (current.max_concurrent.max(1), options.max_concurrent.max(1));
+        if new > old {
+            self.semaphore.add_permits(new - old);
+        } else if new < old {
+            let semaphore = self.semaphore.clone();
+            tokio::spawn(async move {
+                if let Ok(permits) = semaphore.acquire_many_owned((old - new) as u32).await {
+                    permits.forget();
+                }
+            });
+        }
+        *current = options;
+    }
+
+    pub fn subscribe(&self) -> BackendReceiver<Download> {
+        let downloads = self.downloads.lock().clone();
+        self.sender.subscribe(downloads)
+    }
+
+    // Path of the downloaded file of the video if it still exists
+    pub fn local_path(&self, url: &str) -> Option<PathBuf> {
+        self.downloaded
+            .lock()
+            .videos
+            .iter()
+            .find(|downloaded| downloaded.video.url == url)
+            .map(|downloaded| downloaded.path.clone())
+            .filter(|path| path.exists())
+    }
+
+    // Downloads the video once there's a free download slot. Videos that are already downloaded
+    // or being downloaded are skipped.
+    pub async fn download(&self, video: Video) -> Result<(), ConfigError> {
+        if self.local_path(&video.url).is_some() || !self.queue(&video) {
+            return Ok(());
+        }
+
+        let cancel = Arc::new(Notify::new());
+        self.cancels
+            .lock()
+            .insert(video.url.clone(), cancel.clone());
+
+        let result = select! {
+            permit = self.semaphore.acquire() => match permit {
+                Ok(_permit) => {
+                    self.set_state(&video.url, DownloadState::Downloading(Default::default()));
+                    let options = self.options.lock().clone();
+                    yt_dlp::download(
+                        &options.yt_dlp,
+                        &video.url,
+                        options.format.as_deref(),
+                        &options.output,
+                        &cancel,
+                        |progress| self.set_state(&video.url, DownloadState::Downloading(progress)),
+                    )
+                    .await
+                }
+                Err(_) => Err(YtDlpError::Cancelled),
+            },
+            _ = cancel.notified() => Err(YtDlpError::Cancelled),
+        };
+        self.cancels.lock().remove(&video.url);
+
+        match result {
+            Ok(path) => {
+                self.set_state(&video.url, DownloadState::Finished(path.clone()));
+                self.save_downloaded(DownloadedVideo { path, video })
+                    .await?;
+            }
+            Err(YtDlpError::Cancelled) => self.set_state(&video.url, DownloadState::Cancelled),
+            Err(error) => self.set_state(&video.url, DownloadState::Failed(error.to_string())),
+        }
+        Ok(())
+    }
+
+    pub fn cancel(&self, url: &str) {
+        if let Some(cancel) = self.cancels.lock().get(url) {
+            cancel.notify_one();
+        }
+    }
+
+    // Adds the video to the queue and returns false if it's already queued or downloading
+    fn queue(&self, video: &Video) -> bool {
+        let mut downloads = self.downloads.lock();
+        match downloads
+            .iter_mut()
+            .find(|download| download.video.url == video.url)
+        {
+            Some(download) if download.is_active() => false,
+            Some(download) => {
+                download.state = DownloadState::Queued;
+                self.sender.send(BackendMessage::Update(download.clone()));
+                true
+            }
+            None => {
+                let download = Download {
+                    id: downloads.len(),
+                    video: video.clone(),
+                    state: DownloadState::Queued,
+                };
+                downloads.push(download.clone());
+                self.sender.send(BackendMessage::New(download));
+                true
+            }
+        }
+    }
+
+    fn set_state(&self, url: &str, state: DownloadState) {
+        let mut downloads = self.downloads.lock();
+        if let Some(download) = downloads
+            .iter_mut()
+            .find(|download| download.video.url == url)
+        {
+            download.state = state;
+            self.sender.send(BackendMessage::Update(download.clone()));
+        }
+    }
+
+    async fn save_downloaded(&self, downloaded_video: DownloadedVideo) -> Result<(), ConfigError> {
+        let new_downloaded = {
+            let mut downloaded = self.downloaded.lock();
+            downloaded
+                .videos
+                .retain(|existing| existing.video.url != downloaded_video.video.url);
+            downloaded.videos.push(downloaded_video);
+            downloaded.clone()
+        };
+
+        let mut file_handler = self.file_handler.lock().await;
+        file_handler.write(&new_downloaded).await
+    }
+}
@@ -1,12 +1,34 @@
 use crate::config_error::ConfigError;
 
-use serde::{de::DeserializeOwned, Serialize};
-use std::{io, path::PathBuf};
+use chrono::{DateTime, Local};
+use serde::{Serialize, de::DeserializeOwned};
+use std::{
+    fs::Permissions,
+    io,
+    os::unix::fs::MetadataExt,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 use tokio::{fs, fs::File, io::AsyncWriteExt};
 
+const BACKUP_DIR: &str = "backups";
+// Number of previous versions that are kept of every file
+const BACKUP_COUNT: usize = 5;
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
 pub struct ConfigFileHandler<C> {
     config_type: std::marker::PhantomData<C>,
     path: PathBuf,
+    // Content of the file as it was last read or written, which config files are compared to
+    // before writing to avoid overwriting changes made by others
+    content: Option<String>,
+    // Content that was last seen when reloading, whether or not it was valid
+    checked: Option<String>,
+    // Config files are edited by users, so changes made by others are detected before writing and
+    // the replaced versions are backed up
+    user_edited: bool,
 }
 
 impl<C: Serialize + DeserializeOwned + Default + Clone> ConfigFileHandler<C> {
@@ -14,20 +36,57 @@ impl<C: Serialize + DeserializeOwned + Default + Clone> ConfigFileHandler<C> {
         let config_file_name = format!("{}.toml", config_name);
         let mut path = Self::ensure_config_dir_exists().await?;
         path.push(config_file_name);
+        Ok(Self::with_path(path, true))
+    }
+
+    // State files are kept in the state dir, and are moved there from the config dir where they
+    // used to be kept
+    pub async fn from_state_file(state_name: &str) -> Result<Self, ConfigError> {
+        let state_file_name = format!("{}.toml", state_name);
+        let dir = find_state_dir()?;
+        fs::create_dir_all(&dir)
+            .await
+            .map_err(ConfigError::CreateConfigDir)?;
+        let path = dir.join(&state_file_name);
 
-        Ok(Self {
+        let old_path = find_config_dir()?.join(&state_file_name);
+        if !fs::try_exists(&path).await.unwrap_or(true)
+            && fs::try_exists(&old_path).await.unwrap_or(false)
+        {
+            move_file(&old_path, &path)
+                .await
+                .map_err(ConfigError::MigrateStateFile)?;
+        }
+        Ok(Self::with_path(path, false))
+    }
+
+    pub(crate) fn with_path(path: PathBuf, user_edited: bool) -> Self {
+        Self {
             config_type: std::marker::PhantomData,
             path,
-        })
+            content: None,
+            checked: None,
+            user_edited,
+        }
     }
 
     pub async fn read(&mut self) -> Result<C, ConfigError> {
+        self.read_or_else(Default::default).await
+    }
+
+    // Reads the file, or creates it with the provided default if it doesn't exist
+    pub async fn read_or_else(&mut self, default: impl FnOnce() -> C) -> Result<C, ConfigError> {
         match fs::read_to_string(&self.path).await {
-            Ok(contents) => Ok(toml::from_str(&contents)?),
+            Ok(contents) => {
+                let config = toml::from_str(&contents)?;
+                self.checked = Some(contents.clone());
+                self.content = Some(contents);
+                Ok(config)
+            }
             Err(error) => match error.kind() {
                 io::ErrorKind::NotFound => {
-                    let config = Default::default();
-                    Self::write_to_path(&self.path, &config).await?;
+                    let config = default();
+                    self.write(&config).await?;
                     Ok(config)
                 }
                 _ => Err(ConfigError::ReadConfigFile),
@@ -35,44 +94,326 @@ impl<C: Serialize + DeserializeOwned + Default + Clone> ConfigFileHandler<C> {
         }
     }
 
-    pub async fn write(&self, config: &C) -> Result<(), ConfigError> {
-        Self::write_to_path(&self.path, config).await
+    // Reads the file again if it has changed since it was last read or written. Files that have
+    // been removed are ignored, and invalid files are only reported once.
+    pub async fn reload(&mut self) -> Result<Option<C>, ConfigError> {
+        let contents = match fs::read_to_string(&self.path).await {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(_) => return Err(ConfigError::ReadConfigFile),
+        };
+        if self.checked.as_ref() == Some(&contents) {
+            return Ok(None);
+        }
+
+        self.checked = Some(contents.clone());
+        let config = toml::from_str(&contents)
+            .map_err(|error| ConfigError::InvalidConfigFile(self.path.clone(), error))?;
+        self.content = Some(contents);
+        Ok(Some(config))
     }
 
-    async fn write_to_path(path: &PathBuf, config: &C) -> Result<(), ConfigError> {
+    pub async fn write(&mut self, config: &C) -> Result<(), ConfigError> {
         let toml = toml::to_string(config)?;
-        let mut file = File::create(path)
-            .await
-            .map_err(ConfigError::CreateConfigFile)?;
-        file.write(toml.as_bytes())
-            .await
-            .map_err(ConfigError::WriteConfigFile)?;
-        file.flush().await.map_err(ConfigError::WriteConfigFile)?;
+        if self.user_edited {
+            self.check_conflict().await?;
+        }
+
+        write_atomically(&self.path, toml.as_bytes(), self.user_edited).await?;
+        self.checked = Some(toml.clone());
+        self.content = Some(toml);
         Ok(())
     }
 
+    // Fails if the file has been changed since it was last read or written
+    async fn check_conflict(&self) -> Result<(), ConfigError> {
+        let current = match fs::read_to_string(&self.path).await {
+            Ok(current) => Some(current),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+            Err(_) => return Err(ConfigError::ReadConfigFile),
+        };
+
+        match (&self.content, current) {
+            (Some(content), Some(current)) if *content != current => {
+                Err(ConfigError::Conflict(self.path.clone()))
+            }
+            _ => Ok(()),
+        }
+    }
+
     async fn ensure_config_dir_exists() -> Result<PathBuf, ConfigError> {
-        let dir = Self::find_config_dir()?;
+        let dir = find_config_dir()?;
         fs::create_dir_all(&dir)
             .await
             .map_err(ConfigError::CreateConfigDir)?;
 
         Ok(dir)
     }
+}
+
+// Writes to a temporary file which replaces the file once it's on disk, so that the file is never
+// left partially written. The replaced version is optionally kept as a backup.
+pub async fn write_atomically(
+    path: &Path,
+    content: &[u8],
+    backup: bool,
+) -> Result<(), ConfigError> {
+    let current = match fs::read(path).await {
+        Ok(current) => Some(current),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+        Err(error) => return Err(ConfigError::WriteConfigFile(error)),
+    };
+    if current.as_deref() == Some(content) {
+        return Ok(());
+    }
+
+    // Symlinks, such as to files in a dotfiles repository, are kept and the file they point to is
+    // replaced instead
+    let (target, permissions) = if current.is_some() {
+        let target = fs::canonicalize(path)
+            .await
+            .map_err(ConfigError::WriteConfigFile)?;
+        let metadata = fs::metadata(&target)
+            .await
+            .map_err(ConfigError::WriteConfigFile)?;
+        (target, Some(metadata.permissions()))
+    } else {
+        (path.to_owned(), None)
+    };
+
+    if let Some(current) = current.filter(|_| backup) {
+        save_backup(path, &current).await?;
+    }
+
+    // The counter keeps concurrent writes of the same file from sharing a temporary file
+    let id = TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let mut temp_path = target.as_os_str().to_owned();
+    temp_path.push(format!(".{}.{id}.tmp", process::id()));
+    let temp_path = PathBuf::from(temp_path);
+
+    if let Err(error) = write_temp_file(&temp_path, content, permissions).await {
+        let _ = fs::remove_file(&temp_path).await;
+        return Err(error);
+    }
+
+    if let Err(error) = fs::rename(&temp_path, &target).await {
+        let _ = fs::remove_file(&temp_path).await;
+        return Err(ConfigError::WriteConfigFile(error));
+    }
 
-    fn find_config_dir() -> Result<PathBuf, ConfigError> {
-        let mut path = PathBuf::new();
+    // The rename itself is only on disk once the directory is
+    if let Some(dir) = target.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        let dir = File::open(dir)
+            .await
+            .map_err(ConfigError::WriteConfigFile)?;
+        dir.sync_all().await.map_err(ConfigError::WriteConfigFile)?;
+    }
+    Ok(())
+}
 
-        match std::env::var("XDG_CONFIG_HOME") {
-            Ok(config_dir) => path.push(config_dir),
-            _ => {
-                let home = std::env::var("HOME")?;
-                path.push(home);
-                path.push(".config");
+// The temporary file gets the permissions of the file it replaces
+async fn write_temp_file(
+    path: &Path,
+    content: &[u8],
+    permissions: Option<Permissions>,
+) -> Result<(), ConfigError> {
+    let mut file = File::create(path)
+        .await
+        .map_err(ConfigError::CreateConfigFile)?;
+    if let Some(permissions) = permissions {
+        file.set_permissions(permissions)
+            .await
+            .map_err(ConfigError::WriteConfigFile)?;
+    }
+    file.write_all(content)
+        .await
+        .map_err(ConfigError::WriteConfigFile)?;
+    file.sync_all().await.map_err(ConfigError::WriteConfigFile)
+}
+
+// Renames the file, or copies it if it's moved to another file system
+async fn move_file(from: &Path, to: &Path) -> io::Result<()> {
+    if fs::rename(from, to).await.is_ok() {
+        return Ok(());
+    }
+
+    fs::copy(from, to).await?;
+    fs::remove_file(from).await
+}
+
+fn backup_path(path: &Path, version: usize) -> Option<PathBuf> {
+    let name = path.file_name()?.to_string_lossy();
+    Some(
+        path.parent()?
+            .join(BACKUP_DIR)
+            .join(format!("{name}.{version}")),
+    )
+}
+
+// Rotates the backups of the file, where 1 is the newest, and saves the content as the newest
+async fn save_backup(path: &Path, content: &[u8]) -> Result<(), ConfigError> {
+    let Some(newest) = backup_path(path, 1) else {
+        return Ok(());
+    };
+    if let Some(dir) = newest.parent() {
+        fs::create_dir_all(dir).await.map_err(ConfigError::Backup)?;
+    }
+
+    for version in (1..BACKUP_COUNT).rev() {
+        let (Some(from), Some(to)) = (backup_path(path, version), backup_path(path, version + 1))
+        else {
+            continue;
+        };
+        match fs::rename(from, to).await {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => {
+                return Err(ConfigError::Backup(error));
             }
+            _ => (),
+        }
+    }
+
+    fs::write(newest, content)
+        .await
+        .map_err(ConfigError::Backup)
+}
+
+pub struct Backup {
+    // Name of the backed up file, such as rss.toml
+    pub name: String,
+    pub version: usize,
+    pub modified: Option<DateTime<Local>>,
+}
+
+// The backups of the config files, ordered by name and from newest to oldest
+pub async fn list_backups() -> Result<Vec<Backup>, ConfigError> {
+    let dir = find_config_dir()?.join(BACKUP_DIR);
+    let mut entries = match fs::read_dir(&dir).await {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(error) => return Err(ConfigError::Backup(error)),
+    };
+
+    let mut backups = vec![];
+    while let Some(entry) = entries.next_entry().await.map_err(ConfigError::Backup)? {
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        let Some((name, version)) = file_name.rsplit_once('.') else {
+            continue;
+        };
+        let Ok(version) = version.parse() else {
+            continue;
+        };
+
+        let modified = entry
+            .metadata()
+            .await
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .map(DateTime::from);
+        backups.push(Backup {
+            name: name.to_owned(),
+            version,
+            modified,
+        });
+    }
+
+    backups.sort_by(|a, b| a.name.cmp(&b.name).then(a.version.cmp(&b.version)));
+    Ok(backups)
+}
+
+// Replaces the config file with the provided name, such as "rss" or "rss.toml", with one of its
+// backups. The replaced file becomes the newest backup, which allows undoing the restore.
+pub async fn restore_backup(name: &str, version: usize) -> Result<PathBuf, ConfigError> {
+    let file_name = if name.ends_with(".toml") {
+        name.to_owned()
+    } else {
+        format!("{name}.toml")
+    };
+
+    let path = find_config_dir()?.join(&file_name);
+    let content = match backup_path(&path, version) {
+        Some(backup) => fs::read_to_string(&backup).await.ok(),
+        None => None,
+    };
+    let content = content.ok_or(ConfigError::MissingBackup(file_name, version))?;
+    // Corrupt backups would only replace a working file with a broken one
+    toml::from_str::<toml::Table>(&content)?;
+
+    write_atomically(&path, content.as_bytes(), true).await?;
+    Ok(path)
+}
+
+pub fn find_config_dir() -> Result<PathBuf, ConfigError> {
+    let mut path = PathBuf::new();
+
+    match std::env::var("XDG_CONFIG_HOME") {
+        Ok(config_dir) => path.push(config_dir),
+        _ => {
+            let home = std::env::var("HOME")?;
+            path.push(home);
+            path.push(".config");
+        }
+    }
+
+    path.push("tuibe");
+    Ok(path)
+}
+
+pub fn find_cache_dir() -> Result<PathBuf, ConfigError> {
+    let mut path = PathBuf::new();
+
+    match std::env::var("XDG_CACHE_HOME") {
+        Ok(cache_dir) => path.push(cache_dir),
+        _ => {
+            let home = std::env::var("HOME")?;
+            path.push(home);
+            path.push(".cache");
+        }
+    }
+
+    path.push("tuibe");
+    Ok(path)
+}
+
+pub fn find_state_dir() -> Result<PathBuf, ConfigError> {
+    let mut path = PathBuf::new();
+
+    match std::env::var("XDG_STATE_HOME") {
+        Ok(state_dir) => path.push(state_dir),
+        _ => {
+            let home = std::env::var("HOME")?;
+            path.push(home);
+            path.push(".local");
+            path.push("state");
         }
+    }
+
+    path.push("tuibe");
+    Ok(path)
+}
 
-        path.push("tuibe");
-        Ok(path)
+// Directory for sockets, which falls back to a directory per user in the temporary directory
+// without XDG_RUNTIME_DIR
+pub fn find_runtime_dir() -> PathBuf {
+    match std::env::var("XDG_RUNTIME_DIR") {
+        Ok(runtime_dir) => PathBuf::from(runtime_dir).join("tuibe"),
+        _ => std::env::temp_dir().join(format!("tuibe-{}", user_id())),
     }
 }
+
+// Creates the directory if needed and returns whether only the user can access it, since it could
+// have been created by another user when it's in the temporary directory
+pub async fn ensure_private_dir(dir: &Path) -> io::Result<bool> {
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)
+        .await?;
+
+    let metadata = fs::symlink_metadata(dir).await?;
+    Ok(metadata.is_dir() && metadata.uid() == user_id() && metadata.mode() & 0o077 == 0)
+}
+
+pub fn user_id() -> u32 {
+    // SAFETY: geteuid has no preconditions and can't fail
+    unsafe { libc::geteuid() }
+}
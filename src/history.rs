@@ -0,0 +1,166 @@
+use crate::{
+    backend::{
+        Video, VideoKind,
+        channel::{BackendMessage, BackendReceiver, BackendSender},
+    },
+    file_handler,
+};
+
+use chrono::{DateTime, Local};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{io, path::PathBuf};
+use thiserror::Error;
+use tokio::{
+    fs::{self, OpenOptions},
+    io::AsyncWriteExt,
+};
+
+const HISTORY_FILE_NAME: &str = "history.jsonl";
+
+#[derive(Debug, Error)]
+pub enum HistoryError {
+    #[error("Failed to find state dir")]
+    FindStateDir,
+
+    #[error("Failed to read or write history: {}", _0)]
+    Io(#[from] io::Error),
+
+    #[error("Failed to serialize history: {}", _0)]
+    Serialize(#[from] serde_json::Error),
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub timestamp: DateTime<Local>,
+    pub url: String,
+    pub title: String,
+    pub author: String,
+    // Player command the video was played with
+    pub player: String,
+}
+
+impl HistoryEntry {
+    pub fn new(video: &Video, player: &str) -> Self {
+        Self {
+            timestamp: Local::now(),
+            url: video.url.clone(),
+            title: video.title.clone(),
+            author: video.author.clone(),
+            player: player.to_owned(),
+        }
+    }
+
+    // The history only contains what's needed to play the video again
+    pub fn video(&self) -> Video {
+        Video {
+            date: self.timestamp.fixed_offset(),
+            title: self.title.clone(),
+            url: self.url.clone(),
+            author: self.author.clone(),
+            feed_url: String::new(),
+            description: String::new(),
+            length: 0,
+            thumbnail: None,
+            views: None,
+            rating: None,
+            kind: VideoKind::Regular,
+        }
+    }
+}
+
+// Play events are appended to a JSON lines file in the state dir
+pub struct HistoryHandler {
+    entries: Mutex<Vec<HistoryEntry>>,
+    path: PathBuf,
+    file_lock: tokio::sync::Mutex<()>,
+    sender: BackendSender<HistoryEntry>,
+}
+
+impl HistoryHandler {
+    pub async fn load() -> Result<Self, HistoryError> {
+        let mut path = file_handler::find_state_dir().map_err(|_| HistoryError::FindStateDir)?;
+        fs::create_dir_all(&path).await?;
+        path.push(HISTORY_FILE_NAME);
+
+        let entries = match fs::read_to_string(&path).await {
+            // Lines that can't be parsed, e.g. a partially written last line, are skipped
+            Ok(contents) => contents
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect(),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => vec![],
+            Err(error) => return Err(error.into()),
+        };
+
+        Ok(Self {
+            entries: Mutex::new(entries),
+            path,
+            file_lock: tokio::sync::Mutex::new(()),
+            sender: BackendSender::new(),
+        })
+    }
+
+    pub fn subscribe(&self) -> BackendReceiver<HistoryEntry> {
+        let entries = self.entries.lock().clone();
+        self.sender.subscribe(entries)
+    }
+
+    pub async fn record(&self, entries: Vec<HistoryEntry>) -> Result<(), HistoryError> {
+        let lines = Self::to_lines(&entries)?;
+        {
+            let _file_lock = self.file_lock.lock().await;
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)
+                .await?;
+            file.write_all(lines.as_bytes()).await?;
+            file.flush().await?;
+        }
+
+        let mut existing_entries = self.entries.lock();
+        for entry in entries {
+            existing_entries.push(entry.clone());
+            self.sender.send(BackendMessage::New(entry));
+        }
+        Ok(())
+    }
+
+    // Removes the entries played within the range, where a missing bound means that the range is
+    // unbounded in that direction
+    pub async fn clear(
+        &self,
+        from: Option<DateTime<Local>>,
+        to: Option<DateTime<Local>>,
+    ) -> Result<(), HistoryError> {
+        let in_range = |entry: &HistoryEntry| {
+            from.is_none_or(|from| entry.timestamp >= from)
+                && to.is_none_or(|to| entry.timestamp < to)
+        };
+
+        let _file_lock = self.file_lock.lock().await;
+        let remaining = {
+            let mut entries = self.entries.lock();
+            let (removed, remaining): (Vec<_>, Vec<_>) =
+                entries.drain(..).partition(|entry| in_range(entry));
+            entries.extend(remaining.iter().cloned());
+            for entry in removed {
+                self.sender.send(BackendMessage::Remove(entry));
+            }
+            remaining
+        };
+
+        fs::write(&self.path, Self::to_lines(&remaining)?).await?;
+        Ok(())
+    }
+
+    fn to_lines(entries: &[HistoryEntry]) -> Result<String, HistoryError> {
+        let mut lines = String::new();
+        for entry in entries {
+            lines.push_str(&serde_json::to_string(entry)?);
+            lines.push('\n');
+        }
+        Ok(lines)
+    }
+}
@@ -0,0 +1,139 @@
+use crate::backend::Video;
+
+use serde::{Deserialize, Serialize};
+use std::{io, process::Stdio};
+use thiserror::Error;
+use tokio::{io::AsyncWriteExt, process::Command};
+
+#[derive(Debug, Error)]
+pub enum HookError {
+    #[error("Failed to run {} hook: {}", _0, _1)]
+    Run(&'static str, #[source] io::Error),
+
+    #[error("The {} hook exited with {}", _0, _1)]
+    Status(&'static str, std::process::ExitStatus),
+
+    #[error("Failed to serialize {} hook event: {}", _0, _1)]
+    Serialize(&'static str, #[source] serde_json::Error),
+}
+
+// Commands run through `sh -c` when the events happen
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HooksConfig {
+    pub on_new_video: Option<String>,
+    pub on_play: Option<String>,
+    pub on_feed_added: Option<String>,
+    pub on_fetch_error: Option<String>,
+}
+
+// The event is passed to the hook as JSON on stdin
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum HookEvent {
+    NewVideo { video: Video },
+    Play { video: Video, player: String },
+    FeedAdded { url: String },
+    FetchError { url: String, error: String },
+}
+
+impl HookEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::NewVideo { .. } => "on_new_video",
+            Self::Play { .. } => "on_play",
+            Self::FeedAdded { .. } => "on_feed_added",
+            Self::FetchError { .. } => "on_fetch_error",
+        }
+    }
+
+    // The most useful fields are also passed as environment variables
+    fn variables(&self) -> Vec<(&'static str, String)> {
+        let video_variables = |video: &Video| {
+            vec![
+                ("TUIBE_URL", video.url.clone()),
+                ("TUIBE_TITLE", video.title.clone()),
+                ("TUIBE_AUTHOR", video.author.clone()),
+                ("TUIBE_FEED_URL", video.feed_url.clone()),
+                ("TUIBE_DATE", video.date.to_rfc3339()),
+            ]
+        };
+
+        let mut variables = match self {
+            Self::NewVideo { video } => video_variables(video),
+            Self::Play { video, player } => {
+                let mut variables = video_variables(video);
+                variables.push(("TUIBE_PLAYER", player.clone()));
+                variables
+            }
+            Self::FeedAdded { url } => vec![("TUIBE_FEED_URL", url.clone())],
+            Self::FetchError { url, error } => vec![
+                ("TUIBE_FEED_URL", url.clone()),
+                ("TUIBE_ERROR", error.clone()),
+            ],
+        };
+        // Named like the event in the JSON
+        let event = self.name().trim_start_matches("on_");
+        variables.push(("TUIBE_EVENT", event.to_owned()));
+        variables
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Hooks {
+    config: HooksConfig,
+}
+
+impl Hooks {
+    pub fn new(config: HooksConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn has_new_video_hook(&self) -> bool {
+        self.config.on_new_video.is_some()
+    }
+
+    fn command(&self, event: &HookEvent) -> Option<&str> {
+        match event {
+            HookEvent::NewVideo { .. } => self.config.on_new_video.as_deref(),
+            HookEvent::Play { .. } => self.config.on_play.as_deref(),
+            HookEvent::FeedAdded { .. } => self.config.on_feed_added.as_deref(),
+            HookEvent::FetchError { .. } => self.config.on_fetch_error.as_deref(),
+        }
+    }
+
+    // Runs the hook of the event, if there is one, and waits for it to exit
+    pub async fn run(&self, event: HookEvent) -> Result<(), HookError> {
+        let Some(command) = self.command(&event) else {
+            return Ok(());
+        };
+
+        let name = event.name();
+        let input =
+            serde_json::to_string(&event).map_err(|error| HookError::Serialize(name, error))?;
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .envs(event.variables())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|error| HookError::Run(name, error))?;
+
+        // Hooks that don't read their input close stdin early, which isn't an error
+        if let Some(mut stdin) = child.stdin.take() {
+            let _ = stdin.write_all(input.as_bytes()).await;
+        }
+
+        let status = child
+            .wait()
+            .await
+            .map_err(|error| HookError::Run(name, error))?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(HookError::Status(name, status))
+        }
+    }
+}
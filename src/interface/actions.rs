@@ -1,31 +1,47 @@
-use super::{error_handler::ErrorMessage, status_label::StatusLabelMessage, ui::UiMessage};
+use super::{
+    error_handler::ErrorMessage,
+    overlay::{Overlay, Overlays},
+    status_label::StatusLabelMessage,
+    ui::UiMessage,
+};
+
+use crate::clipboard::Clipboard;
 
 use parking_lot::Mutex;
+use ratatui::buffer::Buffer;
 use std::{fmt::Display, sync::Arc};
 
 #[derive(Clone)]
 pub struct Actions {
     ui_sender: flume::Sender<UiMessage>,
+    overlays: Overlays,
 
     error_sender: flume::Sender<ErrorMessage>,
 
     status_label_sender: flume::Sender<StatusLabelMessage>,
     status_label_id_counter: Arc<Mutex<usize>>,
+
+    // Set while a text field is being edited so that keys aren't handled as commands
+    text_input: Arc<Mutex<bool>>,
 }
 
 impl Actions {
     pub fn new(
         ui_sender: flume::Sender<UiMessage>,
+        overlays: Overlays,
         error_sender: flume::Sender<ErrorMessage>,
         status_label_sender: flume::Sender<StatusLabelMessage>,
     ) -> Self {
         Self {
             ui_sender,
+            overlays,
 
             error_sender,
 
             status_label_sender,
             status_label_id_counter: Arc::new(Mutex::new(0)),
+
+            text_input: Arc::new(Mutex::new(false)),
         }
     }
 }
@@ -50,6 +66,24 @@ impl Actions {
         self.handle_result_async(self.ui_sender.send_async(UiMessage::Redraw).await, false)
             .await;
     }
+
+    pub fn draw_overlay(&self, buffer: &mut Buffer, overlay: Overlay) {
+        self.overlays.draw(buffer, overlay);
+    }
+
+    // Writes the escape sequence after the next frame so that it isn't written in the middle of one
+    pub fn write_sequence(&self, sequence: String) {
+        self.overlays.write(sequence);
+        self.redraw();
+    }
+
+    pub fn set_text_input(&self, text_input: bool) {
+        *self.text_input.lock() = text_input;
+    }
+
+    pub fn is_text_input(&self) -> bool {
+        *self.text_input.lock()
+    }
 }
 
 // Implement error actions
@@ -123,6 +157,20 @@ impl Actions {
     }
 }
 
+// Implement clipboard actions
+#[allow(dead_code)]
+impl Actions {
+    pub fn copy_to_clipboard(&self, clipboard: Clipboard, text: String) {
+        let actions = self.clone();
+        tokio::spawn(async move {
+            let result = clipboard
+                .copy(&text, |sequence| actions.write_sequence(sequence))
+                .await;
+            actions.handle_result_async(result, true).await;
+        });
+    }
+}
+
 // Implement status label actions
 #[allow(dead_code)]
 impl Actions {
@@ -142,6 +190,14 @@ impl Actions {
         id
     }
 
+    pub fn update_status(&self, id: usize, label: &str) {
+        self.handle_result(
+            self.status_label_sender
+                .send(StatusLabelMessage::Show(id, label.to_owned())),
+            true,
+        );
+    }
+
     pub fn finish_status(&self, id: usize) {
         self.handle_result(
             self.status_label_sender
@@ -3,6 +3,7 @@ use super::{
     backend_provider::BackendProvider,
     component::{Component, Frame},
     error_handler::ErrorHandler,
+    overlay::Overlays,
     status_label::StatusLabel,
     ui::UiMessage,
 };
@@ -21,12 +22,16 @@ pub struct App {
 }
 
 impl App {
-    pub fn new(ui_sender: flume::Sender<UiMessage>) -> Self {
+    pub fn new(
+        ui_sender: flume::Sender<UiMessage>,
+        overlays: Overlays,
+        player: Option<String>,
+    ) -> Self {
         let (error_sender, error_receiver) = flume::unbounded();
         let (status_label_sender, status_label_receiver) = flume::unbounded();
 
-        let actions = Actions::new(ui_sender, error_sender, status_label_sender);
-        let config_provider = BackendProvider::new(actions.clone());
+        let actions = Actions::new(ui_sender, overlays, error_sender, status_label_sender);
+        let config_provider = BackendProvider::new(actions.clone(), player);
 
         let error_handler = ErrorHandler::new(actions.clone(), error_receiver, config_provider);
         let status_label = StatusLabel::new(actions.clone(), status_label_receiver);
@@ -43,11 +48,11 @@ impl App {
 
         for (key, description) in events.iter() {
             let label = format!("{key}: {description}");
-            if let Some(last) = lines.last_mut() {
-                if last.len() + label.len() + 3 < width.into() {
-                    last.push_str(&format!(" | {}", label));
-                    continue;
-                }
+            if let Some(last) = lines.last_mut()
+                && last.len() + label.len() + 3 < width.into()
+            {
+                last.push_str(&format!(" | {}", label));
+                continue;
             }
 
             lines.push(label);
@@ -85,7 +90,11 @@ impl Component for App {
 
     fn handle_event(&mut self, event: Event, size: Option<Size>) {
         match event {
-            Event::Key(event) if event.code == KeyCode::Char('q') => self.actions.quit(),
+            Event::Key(event)
+                if event.code == KeyCode::Char('q') && !self.actions.is_text_input() =>
+            {
+                self.actions.quit()
+            }
             Event::Resize(_, _) => self.actions.redraw(),
             _ => self.error_handler.handle_event(event, size),
         }
@@ -70,6 +70,11 @@ impl RssBackendView {
             BackendMessage::Error(_) => return, // Errors should be handled through feed_view
             BackendMessage::FinishedFetching => return, // Not necessary since there's no indicator
             BackendMessage::New(feed) => list.lock().add(feed),
+            BackendMessage::Update(feed) => {
+                let mut list = list.lock();
+                list.remove(&feed);
+                list.add(feed);
+            }
             BackendMessage::Remove(feed) => list.lock().remove(&feed),
             BackendMessage::Clear => list.lock().clear(),
         }
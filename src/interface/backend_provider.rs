@@ -5,24 +5,41 @@ use super::{
     status_label::LOADING_STRING,
 };
 use crate::backend::{Backend, BackendError, rss::RssBackend};
-use crate::config::ConfigHandler;
+use crate::{
+    config::ConfigHandler, download::DownloadManager, history::HistoryHandler,
+    playback::PlaybackHandler, starred::StarredHandler, watch_later::WatchLaterHandler,
+};
 
 use crossterm::event::Event;
 use parking_lot::Mutex;
 use ratatui::layout::{Rect, Size};
 use std::sync::Arc;
 
+// The loaded configs and stores shared between the views
+#[derive(Clone)]
+pub struct Handlers {
+    pub config: Arc<ConfigHandler>,
+    pub playback: Arc<PlaybackHandler>,
+    pub history: Arc<HistoryHandler>,
+    pub watch_later: Arc<WatchLaterHandler>,
+    pub starred: Arc<StarredHandler>,
+    pub downloads: Arc<DownloadManager>,
+}
+
 #[derive(Clone)]
 pub struct BackendProvider {
     actions: Actions,
     main_view: Arc<Mutex<Option<MainView>>>,
+    // Player passed on the command line
+    player: Option<String>,
 }
 
 impl BackendProvider {
-    pub fn new(actions: Actions) -> Self {
+    pub fn new(actions: Actions, player: Option<String>) -> Self {
         let mut config_provider = Self {
             actions,
             main_view: Arc::new(Mutex::new(None)),
+            player,
         };
 
         config_provider.init_configs();
@@ -32,9 +49,10 @@ impl BackendProvider {
     fn init_configs(&mut self) {
         let actions = self.actions.clone();
         let main_view = self.main_view.clone();
+        let player = self.player.clone();
 
         tokio::spawn(async move {
-            let init_result = Self::init_configs_impl(actions.clone(), main_view).await;
+            let init_result = Self::init_configs_impl(actions.clone(), main_view, player).await;
             actions.redraw_or_error_async(init_result, false).await;
         });
     }
@@ -42,13 +60,28 @@ impl BackendProvider {
     async fn init_configs_impl(
         actions: Actions,
         main_view: Arc<Mutex<Option<MainView>>>,
+        player: Option<String>,
     ) -> Result<(), BackendError> {
         let finished_loading = actions.show_label(LOADING_STRING);
-        let config = ConfigHandler::load().await?;
-        let backend = Arc::new(RssBackend::load().await?);
+        let config = ConfigHandler::load().await?.with_player_override(player);
+        let handlers = Handlers {
+            downloads: Arc::new(DownloadManager::load(config.download_options()).await?),
+            playback: Arc::new(PlaybackHandler::load().await?),
+            history: Arc::new(HistoryHandler::load().await?),
+            watch_later: Arc::new(WatchLaterHandler::load().await?),
+            starred: Arc::new(StarredHandler::load().await?),
+            config: Arc::new(config),
+        };
+        let backend = Arc::new(
+            RssBackend::load()
+                .await?
+                .with_video_probe(handlers.config.video_probe())
+                .with_hooks(handlers.config.hooks()),
+        );
+        backend.load_cache().await?;
 
         let mut main_view = main_view.lock();
-        *main_view = Some(MainView::new(actions, config, backend.clone()));
+        *main_view = Some(MainView::new(actions, handlers, backend.clone()));
 
         finished_loading();
         Ok(())
@@ -0,0 +1,65 @@
+use super::actions::Actions;
+use crate::{backend::rss::RssBackend, config::ConfigHandler, download::DownloadManager};
+
+use std::{sync::Arc, time::Duration};
+
+// The config files are compared to the loaded ones on every tick
+const RELOAD_INTERVAL: Duration = Duration::from_secs(2);
+
+// Reloads config.toml and rss.toml when they're changed by others, such as when editing or syncing
+// them, so that the changes are used and aren't overwritten by the loaded versions
+pub struct ConfigWatcher {
+    actions: Actions,
+    config: Arc<ConfigHandler>,
+    backend: Arc<RssBackend>,
+    downloads: Arc<DownloadManager>,
+}
+
+impl ConfigWatcher {
+    pub fn start(
+        actions: Actions,
+        config: Arc<ConfigHandler>,
+        backend: Arc<RssBackend>,
+        downloads: Arc<DownloadManager>,
+    ) {
+        let watcher = Self {
+            actions,
+            config,
+            backend,
+            downloads,
+        };
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(RELOAD_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                watcher.reload().await;
+            }
+        });
+    }
+
+    async fn reload(&self) {
+        // The probe is only replaced when it changes to keep limiting the concurrent probes. The
+        // hidden kinds are applied by the feed view when drawing.
+        let video_probe = self.config.video_probe();
+        match self.config.reload().await {
+            Ok(true) => {
+                self.backend.set_hooks(self.config.hooks());
+                self.downloads.set_options(self.config.download_options());
+                if video_probe != self.config.video_probe() {
+                    self.backend.set_video_probe(self.config.video_probe());
+                }
+                self.actions.redraw_async().await;
+            }
+            Ok(false) => (),
+            Err(error) => self.actions.handle_error_async(error, true).await,
+        }
+
+        match self.backend.reload_config().await {
+            Ok(true) => self.actions.redraw_async().await,
+            Ok(false) => (),
+            Err(error) => self.actions.handle_error_async(error, true).await,
+        }
+    }
+}
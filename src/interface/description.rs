@@ -0,0 +1,476 @@
+use super::{
+    actions::Actions,
+    component::{Component, Frame},
+    list::{List, Same},
+    overlay::Overlay,
+};
+use crate::{
+    backend::chapter::{self, Chapter},
+    config::ConfigHandler,
+};
+
+use crossterm::{
+    event::{Event, KeyCode},
+    style::{self as terminal_style, Stylize, style},
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Rect, Size},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, ListItem, Paragraph},
+};
+use std::sync::Arc;
+use unicode_width::UnicodeWidthStr;
+
+const LINK_PREFIXES: [&str; 2] = ["https://", "http://"];
+// The color of the links, which is `Color::Blue` of ratatui
+const LINK_COLOR: terminal_style::Color = terminal_style::Color::DarkBlue;
+
+// A piece of text which is either plain text or part of the link with the provided index
+struct Segment {
+    text: String,
+    link: Option<usize>,
+}
+
+impl From<Chapter> for ListItem<'static> {
+    fn from(value: Chapter) -> Self {
+        let (hours, minutes, seconds) =
+            (value.start / 3600, value.start / 60 % 60, value.start % 60);
+        ListItem::new(format!(
+            " {hours}:{minutes:02}:{seconds:02} {}",
+            value.title
+        ))
+    }
+}
+
+impl Same for Chapter {
+    fn same(&self, other: &Self) -> bool {
+        self.start == other.start
+    }
+}
+
+pub struct Description {
+    actions: Actions,
+    config: Arc<ConfigHandler>,
+    video_url: Option<String>,
+    text: String,
+    links: Vec<String>,
+    scroll: usize,
+    height: usize,
+    focused: bool,
+    fullscreen: bool,
+    link_index: String,
+    chapters: List<Chapter>,
+    show_chapters: bool,
+    // Start of the chapter picked for playback, taken by the owner of the description
+    requested_start: Option<u32>,
+}
+
+impl Description {
+    pub fn new(actions: Actions, config: Arc<ConfigHandler>) -> Self {
+        Self {
+            actions,
+            config,
+            video_url: None,
+            text: String::new(),
+            links: vec![],
+            scroll: 0,
+            height: 0,
+            focused: false,
+            fullscreen: false,
+            link_index: String::new(),
+            chapters: List::new(),
+            show_chapters: false,
+            requested_start: None,
+        }
+    }
+
+    pub fn set_video(&mut self, video_url: Option<String>, text: String) {
+        if self.video_url != video_url {
+            self.video_url = video_url;
+            self.links = Self::find_links(&text);
+            self.chapters.clear();
+            chapter::parse(&text)
+                .into_iter()
+                .for_each(|chapter| self.chapters.add(chapter));
+            self.show_chapters = false;
+            self.text = text;
+            self.scroll = 0;
+            self.link_index.clear();
+        }
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn is_fullscreen(&self) -> bool {
+        self.fullscreen
+    }
+
+    pub fn focus(&mut self) {
+        self.focused = true;
+    }
+
+    pub fn take_requested_start(&mut self) -> Option<u32> {
+        self.requested_start.take()
+    }
+
+    fn unfocus(&mut self) {
+        self.focused = false;
+        self.fullscreen = false;
+        self.show_chapters = false;
+        self.link_index.clear();
+    }
+
+    fn toggle_chapters(&mut self) {
+        self.show_chapters = !self.show_chapters && self.chapters.get_current_item().is_some();
+    }
+
+    fn play_chapter(&mut self) {
+        self.requested_start = self
+            .chapters
+            .get_current_item()
+            .map(|chapter| chapter.start);
+    }
+
+    fn draw_chapters(&mut self, f: &mut Frame, area: Rect, block: Block) {
+        let inner_area = block.inner(area);
+        let list = self
+            .chapters
+            .list(inner_area.height.into())
+            .block(block)
+            .style(Style::default().fg(Color::White));
+        f.render_widget(list, area);
+    }
+
+    fn handle_chapters_event(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc | KeyCode::Char('C') => self.show_chapters = false,
+            KeyCode::Up | KeyCode::Char('k') => self.chapters.move_up(1),
+            KeyCode::Down | KeyCode::Char('j') => self.chapters.move_down(1),
+            KeyCode::Char('g') => self.chapters.move_top(),
+            KeyCode::Char('G') => self.chapters.move_bottom(),
+            KeyCode::Char('f') => self.fullscreen = !self.fullscreen,
+            KeyCode::Enter => self.play_chapter(),
+            _ => return,
+        }
+
+        self.actions.redraw();
+    }
+
+    fn scroll_down(&mut self, steps: usize) {
+        self.scroll = self.scroll.saturating_add(steps);
+    }
+
+    fn scroll_up(&mut self, steps: usize) {
+        self.scroll = self.scroll.saturating_sub(steps);
+    }
+
+    // Returns the link matching the typed index, or the first link if no index has been typed
+    fn take_link(&mut self) -> Option<String> {
+        let index = self.link_index.parse::<usize>().unwrap_or(1);
+        self.link_index.clear();
+        self.links.get(index.checked_sub(1)?).cloned()
+    }
+
+    fn open_link(&mut self) {
+        if let Some(link) = self.take_link() {
+            let actions = self.actions.clone();
+            let browser = self.config.browser();
+            tokio::spawn(async move {
+                actions
+                    .redraw_or_error_async(browser.open(&link).await, true)
+                    .await;
+            });
+        }
+    }
+
+    fn copy_link(&mut self) {
+        if let Some(link) = self.take_link() {
+            self.actions
+                .copy_to_clipboard(self.config.clipboard(), link);
+        }
+    }
+
+    fn find_links(text: &str) -> Vec<String> {
+        text.lines()
+            .flat_map(|line| line.split(' '))
+            .filter_map(Self::link_in_word)
+            .map(|(_, link, _)| link.to_owned())
+            .collect()
+    }
+
+    // Splits a word into the text before the link, the link and the text after the link
+    fn link_in_word(word: &str) -> Option<(&str, &str, &str)> {
+        let start = LINK_PREFIXES
+            .iter()
+            .filter_map(|prefix| word.find(prefix))
+            .min()?;
+        let (before, rest) = word.split_at(start);
+        let link = rest.trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']', '}', '"', '\'']);
+        let after = &rest[link.len()..];
+
+        LINK_PREFIXES
+            .iter()
+            .all(|prefix| link.len() > prefix.len() || !link.starts_with(prefix))
+            .then_some((before, link, after))
+    }
+
+    fn segments(&self) -> Vec<Vec<Segment>> {
+        let mut link_count = 0;
+        self.text
+            .lines()
+            .map(|line| {
+                let mut segments = vec![];
+                for (i, word) in line.split(' ').enumerate() {
+                    if i > 0 {
+                        segments.push(Segment {
+                            text: String::from(" "),
+                            link: None,
+                        });
+                    }
+
+                    match Self::link_in_word(word) {
+                        Some((before, link, after)) => {
+                            segments.push(Segment {
+                                text: before.to_owned(),
+                                link: None,
+                            });
+                            segments.push(Segment {
+                                text: link.to_owned(),
+                                link: Some(link_count),
+                            });
+                            segments.push(Segment {
+                                text: format!("[{}]{after}", link_count + 1),
+                                link: None,
+                            });
+                            link_count += 1;
+                        }
+                        None => segments.push(Segment {
+                            text: word.to_owned(),
+                            link: None,
+                        }),
+                    }
+                }
+                segments
+            })
+            .collect()
+    }
+
+    // Wraps the segments into lines that fit the width. Words are moved to the next line if they
+    // don't fit, and words longer than the width are split.
+    fn wrap(lines: Vec<Vec<Segment>>, width: usize) -> Vec<Vec<Segment>> {
+        let mut wrapped = vec![];
+        for segments in lines {
+            let mut line: Vec<Segment> = vec![];
+            let mut line_width = 0;
+            for segment in segments {
+                let mut text = segment.text;
+                loop {
+                    let segment_width = text.width();
+                    if line_width + segment_width <= width {
+                        line_width += segment_width;
+                        line.push(Segment {
+                            text,
+                            link: segment.link,
+                        });
+                        break;
+                    } else if text == " " {
+                        break;
+                    } else if segment_width <= width && line_width > 0 {
+                        wrapped.push(std::mem::take(&mut line));
+                        line_width = 0;
+                    } else {
+                        let split_index = Self::split_index(&text, width - line_width);
+                        let rest = text.split_off(split_index);
+                        line.push(Segment {
+                            text,
+                            link: segment.link,
+                        });
+                        wrapped.push(std::mem::take(&mut line));
+                        line_width = 0;
+                        text = rest;
+                    }
+                }
+            }
+            wrapped.push(line);
+        }
+        wrapped
+    }
+
+    fn split_index(text: &str, width: usize) -> usize {
+        let mut total_width = 0;
+        for (index, character) in text.char_indices() {
+            total_width += character.to_string().width();
+            if total_width > width {
+                return index.max(character.len_utf8());
+            }
+        }
+        text.len()
+    }
+
+    // Draws the links as OSC 8 hyperlinks on top of the paragraph. The escape sequences are written
+    // as overlays since the buffer would take their length as the width of the text.
+    fn render_hyperlinks(&self, buffer: &mut Buffer, area: Rect, lines: &[Vec<Segment>]) {
+        for (y, line) in lines
+            .iter()
+            .skip(self.scroll)
+            .take(area.height.into())
+            .enumerate()
+        {
+            let mut x = 0;
+            for segment in line {
+                let width = segment.text.width();
+                let link = segment.link.and_then(|index| self.links.get(index));
+                let link_area = Rect::new(
+                    area.x.saturating_add(u16::try_from(x).unwrap_or(u16::MAX)),
+                    area.y + y as u16,
+                    u16::try_from(width).unwrap_or(u16::MAX),
+                    1,
+                );
+                if let Some(link) = link
+                    && width > 0
+                    && area.intersection(link_area) == link_area
+                {
+                    let text = style(&segment.text).with(LINK_COLOR).underlined();
+                    let overlay = Overlay {
+                        area: link_area,
+                        sequence: format!("\x1b]8;;{link}\x1b\\{text}\x1b]8;;\x1b\\").into(),
+                        clear: None,
+                    };
+                    self.actions.draw_overlay(buffer, overlay);
+                }
+                x += width;
+            }
+        }
+    }
+}
+
+impl Component for Description {
+    fn draw(&mut self, f: &mut Frame, area: Rect) {
+        let mut details = vec![];
+        if !self.links.is_empty() {
+            details.push(format!("{} links", self.links.len()));
+        }
+        if !self.link_index.is_empty() {
+            details.push(format!("link: {}", self.link_index));
+        }
+        let chapter_count = self.chapters.iter().count();
+        if chapter_count > 0 {
+            details.push(format!("{chapter_count} chapters"));
+        }
+
+        let name = if self.show_chapters {
+            "Chapters"
+        } else {
+            "Description"
+        };
+        let title = if details.is_empty() {
+            String::from(name)
+        } else {
+            format!("{name} ({})", details.join(", "))
+        };
+
+        let border_style = if self.focused {
+            Style::default().fg(Color::Green)
+        } else {
+            Style::default()
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::TOP)
+            .border_style(border_style);
+        if self.show_chapters {
+            self.draw_chapters(f, area, block);
+            return;
+        }
+
+        let inner_area = block.inner(area);
+
+        let lines = Self::wrap(self.segments(), inner_area.width.into());
+        self.height = inner_area.height.into();
+        self.scroll = self.scroll.min(lines.len().saturating_sub(self.height));
+
+        let link_style = Style::default()
+            .fg(Color::Blue)
+            .add_modifier(Modifier::UNDERLINED);
+        let text: Vec<Line> = lines
+            .iter()
+            .map(|line| {
+                Line::from(
+                    line.iter()
+                        .map(|segment| match segment.link {
+                            Some(_) => Span::styled(segment.text.clone(), link_style),
+                            None => Span::raw(segment.text.clone()),
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect();
+
+        let paragraph = Paragraph::new(text)
+            .block(block)
+            .style(Style::default().fg(Color::White))
+            .scroll((u16::try_from(self.scroll).unwrap_or(u16::MAX), 0));
+
+        f.render_widget(paragraph, area);
+        self.render_hyperlinks(f.buffer_mut(), inner_area, &lines);
+    }
+
+    fn handle_event(&mut self, event: Event, _size: Option<Size>) {
+        let Event::Key(event) = event else {
+            return;
+        };
+
+        if self.show_chapters {
+            self.handle_chapters_event(event.code);
+            return;
+        }
+
+        match event.code {
+            KeyCode::Esc | KeyCode::Char('i') => self.unfocus(),
+            KeyCode::Up | KeyCode::Char('k') => self.scroll_up(1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll_down(1),
+            KeyCode::Char('u') => self.scroll_up(self.height / 2),
+            KeyCode::Char('d') => self.scroll_down(self.height / 2),
+            KeyCode::Char('g') => self.scroll = 0,
+            KeyCode::Char('G') => self.scroll = usize::MAX,
+            KeyCode::Char('f') => self.fullscreen = !self.fullscreen,
+            KeyCode::Char('o') => self.open_link(),
+            KeyCode::Char('y') => self.copy_link(),
+            KeyCode::Char('C') => self.toggle_chapters(),
+            KeyCode::Char(digit) if digit.is_ascii_digit() => self.link_index.push(digit),
+            KeyCode::Backspace => {
+                self.link_index.pop();
+            }
+            _ => return,
+        }
+
+        self.actions.redraw();
+    }
+
+    fn registered_events(&self) -> Vec<(String, String)> {
+        if self.show_chapters {
+            return vec![
+                (String::from("Esc"), String::from("Back")),
+                (String::from("j"), String::from("Down")),
+                (String::from("k"), String::from("Up")),
+                (String::from("f"), String::from("Toggle fullscreen")),
+                (String::from("Enter"), String::from("Play from chapter")),
+            ];
+        }
+
+        vec![
+            (String::from("Esc"), String::from("Back")),
+            (String::from("j"), String::from("Scroll down")),
+            (String::from("k"), String::from("Scroll up")),
+            (String::from("f"), String::from("Toggle fullscreen")),
+            (String::from("0-9"), String::from("Link number")),
+            (String::from("o"), String::from("Open link")),
+            (String::from("y"), String::from("Copy link")),
+            (String::from("C"), String::from("Chapters")),
+        ]
+    }
+}
@@ -0,0 +1,213 @@
+use super::{
+    actions::Actions,
+    component::{Component, Frame},
+    list::{List, Same},
+    player_launcher::PlayerLauncher,
+};
+use crate::{
+    backend::channel::BackendMessage,
+    download::{Download, DownloadManager, DownloadState},
+};
+
+use crossterm::event::{Event, KeyCode};
+use parking_lot::Mutex;
+use ratatui::{
+    layout::{Rect, Size},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, ListItem},
+};
+use std::{cmp::Ordering, sync::Arc};
+
+#[derive(Clone)]
+struct DownloadItem(Download);
+
+impl From<DownloadItem> for ListItem<'static> {
+    fn from(value: DownloadItem) -> Self {
+        let DownloadItem(download) = value;
+        let (state, color, details) = match download.state {
+            DownloadState::Queued => (String::from("queued"), Color::White, String::new()),
+            DownloadState::Downloading(progress) => (
+                format!("{:.1}%", progress.percent),
+                Color::Cyan,
+                format!(" ({}, ETA {})", progress.speed, progress.eta),
+            ),
+            DownloadState::Finished(path) => (
+                String::from("done"),
+                Color::Green,
+                format!(" ({})", path.display()),
+            ),
+            DownloadState::Failed(error) => {
+                (String::from("failed"), Color::Red, format!(": {error}"))
+            }
+            DownloadState::Cancelled => (String::from("cancelled"), Color::Yellow, String::new()),
+        };
+
+        ListItem::new(Line::from(vec![
+            Span::styled(format!(" [{state:>9}] "), Style::default().fg(color)),
+            Span::raw(format!(
+                "{} - {}{details}",
+                download.video.title, download.video.author
+            )),
+        ]))
+    }
+}
+
+impl Same for DownloadItem {
+    fn same(&self, other: &Self) -> bool {
+        self.0.video.url == other.0.video.url
+    }
+}
+
+impl Ord for DownloadItem {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.id.cmp(&other.0.id)
+    }
+}
+
+impl PartialOrd for DownloadItem {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for DownloadItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for DownloadItem {}
+
+pub struct DownloadsView {
+    actions: Actions,
+    downloads: Arc<DownloadManager>,
+    launcher: PlayerLauncher,
+    list: Arc<Mutex<List<DownloadItem>>>,
+}
+
+impl DownloadsView {
+    pub fn new(
+        actions: Actions,
+        downloads: Arc<DownloadManager>,
+        launcher: PlayerLauncher,
+    ) -> Self {
+        let downloads_view = Self {
+            actions,
+            downloads,
+            launcher,
+            list: Arc::new(Mutex::new(List::new())),
+        };
+
+        downloads_view.listen_download_messages();
+        downloads_view
+    }
+
+    fn listen_download_messages(&self) {
+        let actions = self.actions.clone();
+        let list = self.list.clone();
+        let mut receiver = self.downloads.subscribe();
+        tokio::spawn(async move {
+            while let Some(message) = receiver.recv().await {
+                {
+                    let mut list = list.lock();
+                    match message {
+                        BackendMessage::New(download) => list.add(DownloadItem(download)),
+                        BackendMessage::Update(download) => {
+                            let item = DownloadItem(download);
+                            list.remove(&item);
+                            list.add(item);
+                        }
+                        BackendMessage::Remove(download) => list.remove(&DownloadItem(download)),
+                        BackendMessage::Clear => list.clear(),
+                        BackendMessage::FinishedFetching | BackendMessage::Error(_) => (),
+                    }
+                }
+                actions.redraw_async().await;
+            }
+        });
+    }
+
+    fn current_download(&self) -> Option<Download> {
+        self.list.lock().get_current_item().map(|item| item.0)
+    }
+
+    fn cancel_current(&mut self) {
+        if let Some(download) = self.current_download() {
+            self.downloads.cancel(&download.video.url);
+        }
+    }
+
+    fn retry_current(&mut self) {
+        let Some(download) = self.current_download() else {
+            return;
+        };
+        if !matches!(
+            download.state,
+            DownloadState::Failed(_) | DownloadState::Cancelled
+        ) {
+            return;
+        }
+
+        let downloads = self.downloads.clone();
+        let actions = self.actions.clone();
+        tokio::spawn(async move {
+            let result = downloads.download(download.video).await;
+            actions.handle_result_async(result, true).await;
+        });
+    }
+
+    fn play_current(&mut self) {
+        if let Some(download) = self.current_download()
+            && matches!(download.state, DownloadState::Finished(_))
+        {
+            self.launcher.play(vec![download.video], None, None);
+        }
+    }
+}
+
+impl Component for DownloadsView {
+    fn draw(&mut self, f: &mut Frame, area: Rect) {
+        let list = self.list.lock();
+        let active = list.iter().filter(|item| item.0.is_active()).count();
+        let title = format!("Downloads ({active} active)");
+        let list = list
+            .list(area.height.into())
+            .block(Block::default().title(title))
+            .style(Style::default().fg(Color::White));
+        f.render_widget(list, area);
+    }
+
+    fn handle_event(&mut self, event: Event, size: Option<Size>) {
+        let height = size.map_or(30, |size| size.height.into());
+
+        let Event::Key(event) = event else {
+            return;
+        };
+
+        match event.code {
+            KeyCode::Up | KeyCode::Char('k') => self.list.lock().move_up(1),
+            KeyCode::Down | KeyCode::Char('j') => self.list.lock().move_down(1),
+            KeyCode::Char('d') => self.list.lock().move_down(height / 2),
+            KeyCode::Char('u') => self.list.lock().move_up(height / 2),
+            KeyCode::Char('g') => self.list.lock().move_top(),
+            KeyCode::Char('G') => self.list.lock().move_bottom(),
+            KeyCode::Char('x') => self.cancel_current(),
+            KeyCode::Char('r') => self.retry_current(),
+            KeyCode::Enter | KeyCode::Char('p') => self.play_current(),
+            _ => return,
+        }
+
+        self.actions.redraw();
+    }
+
+    fn registered_events(&self) -> Vec<(String, String)> {
+        vec![
+            (String::from("j"), String::from("Down")),
+            (String::from("k"), String::from("Up")),
+            (String::from("x"), String::from("Cancel")),
+            (String::from("r"), String::from("Retry")),
+            (String::from("Enter"), String::from("Play")),
+        ]
+    }
+}
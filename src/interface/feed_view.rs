@@ -1,11 +1,24 @@
 use super::{
     actions::Actions,
+    backend_provider::Handlers,
     component::{Component, Frame},
+    description::Description,
+    player_launcher::PlayerLauncher,
+    selection_dialog::{Selection, SelectionDialog},
     status_label::LOADING_STRING,
+    thumbnail::{GraphicsProtocol, Thumbnail},
     video_list::VideoList,
 };
 use crate::backend::{Backend, Video, channel::BackendMessage, rss::RssBackend};
-use crate::config::ConfigHandler;
+use crate::{
+    config::{ConfigHandler, ThumbnailMode},
+    download::DownloadManager,
+    playlist::{self, PlaylistFormat},
+    starred::StarredHandler,
+    watch_later::WatchLaterHandler,
+};
+
+use chrono::Local;
 
 use crossterm::event::{Event, KeyCode};
 use parking_lot::Mutex;
@@ -14,29 +27,83 @@ use ratatui::{
     style::{Color, Style},
     widgets::Block,
 };
-use std::{env, process::Stdio, sync::Arc};
-use tokio::process::Command;
-use wl_clipboard_rs::copy::{MimeType, Options, Source};
+use std::{sync::Arc, time::Duration};
+
+const DEFAULT_PROFILE: &str = "default";
+const EXPORT_STATUS_DURATION: Duration = Duration::from_secs(3);
+const METADATA_WIDTH: u16 = 55;
+const MIN_TITLE_WIDTH: u16 = 20;
+
+// The dialogs that can be opened from the feed
+enum FeedDialog {
+    Player,
+    Export,
+}
+
+#[derive(Clone, Copy)]
+enum ExportVideos {
+    Selected,
+    Unwatched,
+}
+
+const EXPORT_OPTIONS: [(ExportVideos, PlaylistFormat); 4] = [
+    (ExportVideos::Selected, PlaylistFormat::M3u8),
+    (ExportVideos::Selected, PlaylistFormat::Xspf),
+    (ExportVideos::Unwatched, PlaylistFormat::M3u8),
+    (ExportVideos::Unwatched, PlaylistFormat::Xspf),
+];
 
 pub struct FeedView {
     actions: Actions,
     config: Arc<ConfigHandler>,
+    launcher: PlayerLauncher,
+    watch_later: Arc<WatchLaterHandler>,
+    starred: Arc<StarredHandler>,
+    downloads: Arc<DownloadManager>,
     backend: Arc<RssBackend>,
     loading_id: Arc<Mutex<Option<usize>>>,
     video_list: Arc<Mutex<VideoList>>,
+    // The thumbnail is created again when the mode is changed in the config
+    thumbnail_mode: ThumbnailMode,
+    thumbnail: Option<Thumbnail>,
+    description: Description,
+    dialog: Option<(FeedDialog, SelectionDialog)>,
 }
 
 impl FeedView {
-    pub fn new(actions: Actions, config: ConfigHandler, backend: Arc<RssBackend>) -> Self {
+    pub fn new(actions: Actions, handlers: &Handlers, backend: Arc<RssBackend>) -> Self {
+        let config = handlers.config.clone();
+        let mut video_list = VideoList::new(config.hidden_kinds());
+        video_list.set_watched(handlers.playback.watched());
+        let video_list = Arc::new(Mutex::new(video_list));
+        let launcher = PlayerLauncher::new(
+            actions.clone(),
+            handlers,
+            backend.clone(),
+            video_list.clone(),
+        );
+        let thumbnail_mode = config.thumbnails();
+        let thumbnail = GraphicsProtocol::from_mode(thumbnail_mode)
+            .map(|protocol| Thumbnail::new(actions.clone(), protocol));
+        let description = Description::new(actions.clone(), config.clone());
         let feed_view = Self {
             actions,
-            config: Arc::new(config),
+            config,
+            launcher,
+            watch_later: handlers.watch_later.clone(),
+            starred: handlers.starred.clone(),
+            downloads: handlers.downloads.clone(),
             backend: backend.clone(),
             loading_id: Default::default(),
-            video_list: Arc::new(Mutex::new(VideoList::new())),
+            video_list,
+            thumbnail_mode,
+            thumbnail,
+            description,
+            dialog: None,
         };
 
         feed_view.listen_backend_messages(backend);
+        feed_view.listen_starred_messages();
         feed_view
     }
 
@@ -65,6 +132,24 @@ impl FeedView {
         });
     }
 
+    fn listen_starred_messages(&self) {
+        let actions = self.actions.clone();
+        let video_list = self.video_list.clone();
+        let mut receiver = self.starred.subscribe();
+        tokio::spawn(async move {
+            while let Some(message) = receiver.recv().await {
+                match message {
+                    BackendMessage::New(video) => video_list.lock().set_starred(&video.url, true),
+                    BackendMessage::Remove(video) => {
+                        video_list.lock().set_starred(&video.url, false)
+                    }
+                    _ => continue,
+                }
+                actions.redraw_async().await;
+            }
+        });
+    }
+
     async fn handle_backend_message(
         message: BackendMessage<Video>,
         loading_id: Arc<Mutex<Option<usize>>>,
@@ -100,116 +185,303 @@ impl FeedView {
     }
 
     fn update_last_played_timestamp(&mut self, last_played_timestamp: i64) {
+        self.launcher
+            .update_last_played_timestamp(last_played_timestamp);
+    }
+
+    // Plays the selected videos, optionally with the provided player profile instead of the
+    // configured ones
+    fn play(&mut self, profile: Option<&str>) {
+        let selected_videos = self.video_list.lock().selected_videos();
+
+        if selected_videos.is_empty() {
+            return;
+        }
+
+        // Tracked videos are marked as watched after playing them, the others by the timestamp
+        let newest_untracked = selected_videos
+            .iter()
+            .filter(|video| !self.launcher.is_tracked(&video.video(), profile))
+            .map(|video| video.date().timestamp())
+            .max();
+        if let Some(new_timestamp) = newest_untracked
+            && new_timestamp > self.config.last_played_timestamp()
         {
-            self.video_list
-                .lock()
-                .update_last_played_timestamp(last_played_timestamp);
+            self.update_last_played_timestamp(new_timestamp);
         }
 
-        let config = self.config.clone();
-        let actions = self.actions.clone();
-        tokio::spawn(async move {
-            actions
-                .redraw_or_error_async(
-                    config
-                        .set_last_played_timestamp(last_played_timestamp)
-                        .await,
-                    true,
-                )
-                .await;
+        let mut videos: Vec<Video> = selected_videos.iter().map(|video| video.video()).collect();
+        videos.sort_by_key(|video| video.date);
+        self.launcher.play(videos, profile, None);
+    }
+
+    // Plays the current video, optionally starting at the provided offset in seconds
+    fn play_current(&mut self, start: Option<u32>) {
+        if let Some(current_video) = self.video_list.lock().current_video() {
+            self.launcher.play(vec![current_video.video()], None, start);
+        }
+    }
+
+    fn open_player_dialog(&mut self) {
+        let mut profiles = vec![String::from(DEFAULT_PROFILE)];
+        profiles.append(&mut self.config.player_profiles());
+        self.dialog = Some((
+            FeedDialog::Player,
+            SelectionDialog::new("Play with", profiles),
+        ));
+    }
+
+    fn open_export_dialog(&mut self) {
+        let options = EXPORT_OPTIONS.map(|(videos, format)| {
+            let videos = match videos {
+                ExportVideos::Selected => "Selected videos",
+                ExportVideos::Unwatched => "Unwatched videos",
+            };
+            format!("{videos} ({})", format.label())
         });
+        self.dialog = Some((
+            FeedDialog::Export,
+            SelectionDialog::new("Export playlist", options.to_vec()),
+        ));
     }
 
-    fn play(&mut self) {
-        let selected_videos = self.video_list.lock().selected_videos();
+    fn handle_dialog_event(&mut self, event: Event, size: Option<Size>) {
+        let Some((ref kind, ref mut dialog)) = self.dialog else {
+            return;
+        };
 
-        if let Some(newest_video) = selected_videos.first() {
-            let finish_status = self.actions.show_label("Playing...");
-            let new_timetamp = newest_video.date().timestamp();
-            if new_timetamp > self.config.last_played_timestamp() {
-                self.update_last_played_timestamp(new_timetamp);
+        dialog.handle_event(event, size);
+        match (dialog.selection(), kind) {
+            (Selection::Pending, _) => (),
+            (Selection::Cancelled, _) => self.dialog = None,
+            (Selection::Selected(index), FeedDialog::Player) => {
+                let profile = dialog
+                    .option(index)
+                    .filter(|profile| *profile != DEFAULT_PROFILE)
+                    .map(str::to_owned);
+                self.dialog = None;
+                self.play(profile.as_deref());
+            }
+            (Selection::Selected(index), FeedDialog::Export) => {
+                self.dialog = None;
+                let (videos, format) = EXPORT_OPTIONS[index];
+                self.export_playlist(videos, format);
             }
+        }
 
-            let player = self.get_player();
-            let actions = self.actions.clone();
-            tokio::spawn(async move {
-                let videos = selected_videos.iter().map(|video| video.url()).rev();
-                let play_result = Command::new(player)
-                    .args(videos)
-                    .stdout(Stdio::null())
-                    .stderr(Stdio::null())
-                    .status()
-                    .await;
-
-                finish_status();
-                actions.redraw_or_error_async(play_result, true).await;
-            });
+        self.actions.redraw();
+    }
+
+    // Writes the videos to a playlist in the configured playlist directory, oldest first
+    fn export_playlist(&mut self, videos: ExportVideos, format: PlaylistFormat) {
+        let mut videos: Vec<Video> = {
+            let video_list = self.video_list.lock();
+            let videos = match videos {
+                ExportVideos::Selected => video_list.selected_videos(),
+                ExportVideos::Unwatched => video_list.unwatched_videos(),
+            };
+            videos.iter().map(|video| video.video()).collect()
+        };
+        if videos.is_empty() {
+            Self::show_export_status(self.actions.clone(), String::from("No videos to export"));
+            return;
         }
+        videos.sort_by_key(|video| video.date);
+
+        let file_name = format!(
+            "tuibe-{}.{}",
+            Local::now().format("%Y%m%d-%H%M%S"),
+            format.extension()
+        );
+        let path = self.config.playlist_dir().join(file_name);
+        let local_files = self.config.playlist_local_files();
+        let downloads = self.downloads.clone();
+        let actions = self.actions.clone();
+        tokio::spawn(async move {
+            let local_path = |url: &str| local_files.then(|| downloads.local_path(url)).flatten();
+            match playlist::export(&path, &videos, local_path).await {
+                Ok(()) => Self::show_export_status(
+                    actions,
+                    format!("Exported playlist to {}", path.display()),
+                ),
+                Err(error) => actions.handle_error_async(error, true).await,
+            }
+        });
     }
 
-    fn play_current(&mut self) {
-        if let Some(current_video) = self.video_list.lock().current_video() {
-            let finish_status = self.actions.show_label("Playing...");
-            let player = self.get_player();
+    fn show_export_status(actions: Actions, label: String) {
+        tokio::spawn(async move {
+            let status_id = actions.start_status(&label);
+            tokio::time::sleep(EXPORT_STATUS_DURATION).await;
+            actions.finish_status(status_id);
+        });
+    }
+
+    // The description and the dialogs handle all keys while they're open
+    pub fn is_capturing_keys(&self) -> bool {
+        self.description.is_focused() || self.dialog.is_some()
+    }
+
+    pub fn launcher(&self) -> PlayerLauncher {
+        self.launcher.clone()
+    }
+
+    fn toggle_watch_later(&mut self) {
+        let Some(current_video) = self.video_list.lock().current_video() else {
+            return;
+        };
+
+        let watch_later = self.watch_later.clone();
+        let actions = self.actions.clone();
+        tokio::spawn(async move {
+            let url = current_video.url();
+            let result = if watch_later.contains(&url) {
+                watch_later.remove(&[url]).await
+            } else {
+                watch_later.add(current_video.video()).await
+            };
+            actions.redraw_or_error_async(result, true).await;
+        });
+    }
+
+    fn toggle_starred(&mut self) {
+        let Some(current_video) = self.video_list.lock().current_video() else {
+            return;
+        };
+
+        let starred = self.starred.clone();
+        let actions = self.actions.clone();
+        tokio::spawn(async move {
+            let result = starred.toggle(current_video.video()).await;
+            actions.redraw_or_error_async(result, true).await;
+        });
+    }
+
+    fn download_selected(&mut self) {
+        let mut videos: Vec<Video> = self
+            .video_list
+            .lock()
+            .selected_videos()
+            .iter()
+            .map(|video| video.video())
+            .collect();
+        videos.sort_by_key(|video| video.date);
+        for video in videos {
+            let downloads = self.downloads.clone();
             let actions = self.actions.clone();
             tokio::spawn(async move {
-                let video = current_video.url();
-                let play_result = Command::new(player)
-                    .arg(video)
-                    .stdout(Stdio::null())
-                    .stderr(Stdio::null())
-                    .status()
-                    .await;
-
-                finish_status();
-                actions.redraw_or_error_async(play_result, true).await;
+                let result = downloads.download(video).await;
+                actions.handle_result_async(result, true).await;
             });
         }
     }
 
     fn copy_current(&mut self) {
         if let Some(current_video) = self.video_list.lock().current_video() {
-            let url = current_video.url();
-            let opts = Options::new();
-            let _ = opts.copy(Source::Bytes(url.into_bytes().into()), MimeType::Autodetect);
+            self.actions
+                .copy_to_clipboard(self.config.clipboard(), current_video.url());
         }
     }
 
-    fn get_player(&self) -> String {
-        env::args()
-            .skip_while(|arg| arg != "--player")
-            .nth(1)
-            .unwrap_or_else(|| self.config.player())
+    fn open_current(&mut self, channel: bool) {
+        let Some(current_video) = self.video_list.lock().current_video() else {
+            return;
+        };
+
+        let video = current_video.video();
+        let url = if channel {
+            match video.channel_url() {
+                Some(url) => url,
+                None => {
+                    return self
+                        .actions
+                        .handle_error(format!("No channel page for {}", video.feed_url), true);
+                }
+            }
+        } else {
+            video.url
+        };
+
+        let browser = self.config.browser();
+        let actions = self.actions.clone();
+        tokio::spawn(async move {
+            actions
+                .handle_result_async(browser.open(&url).await, true)
+                .await;
+        });
+    }
+
+    // Copies the selected videos, or the current one if none are selected
+    fn copy_selected(&mut self) {
+        let videos: Vec<Video> = {
+            let video_list = self.video_list.lock();
+            let mut selected_videos = video_list.selected_videos();
+            if selected_videos.is_empty() {
+                selected_videos.extend(video_list.current_video());
+            }
+            selected_videos.iter().map(|video| video.video()).collect()
+        };
+
+        if !videos.is_empty() {
+            let clipboard = self.config.clipboard();
+            let text = clipboard.format_videos(&videos);
+            self.actions.copy_to_clipboard(clipboard, text);
+        }
     }
 }
 
 impl Component for FeedView {
     fn draw(&mut self, f: &mut Frame, area: Rect) {
         let description_height = 10;
-        let description_y = area.height - description_height;
-        let list_area = Rect::new(area.x, 0, area.width, description_y - 1);
+        let description_y = area.y + area.height - description_height;
+        let list_area = Rect::new(area.x, area.y, area.width, description_y - area.y - 1);
         let description_area = Rect::new(area.x, description_y, area.width, description_height);
 
-        let video_list = self.video_list.lock();
-        let description = video_list.current_description();
+        let thumbnail_mode = self.config.thumbnails();
+        if thumbnail_mode != self.thumbnail_mode {
+            self.thumbnail_mode = thumbnail_mode;
+            self.thumbnail = GraphicsProtocol::from_mode(thumbnail_mode)
+                .map(|protocol| Thumbnail::new(self.actions.clone(), protocol));
+        }
 
-        let metadata_width = 35;
-        let title_area = Rect::new(
-            list_area.x,
-            list_area.y,
-            list_area.width - metadata_width - 3,
-            list_area.height,
+        let mut video_list = self.video_list.lock();
+        video_list.set_hidden_kinds(
+            self.config.hidden_kinds(),
+            self.config.last_played_timestamp(),
+        );
+        self.description.set_video(
+            video_list.current_video().map(|video| video.url()),
+            video_list.current_description(),
         );
+
+        if self.description.is_fullscreen() {
+            self.description.draw(f, area);
+            return;
+        }
+
+        // The metadata is hidden when it would leave too little room for the titles
+        let (title_width, metadata_width) =
+            if list_area.width >= METADATA_WIDTH + MIN_TITLE_WIDTH + 3 {
+                (list_area.width - METADATA_WIDTH - 3, METADATA_WIDTH)
+            } else {
+                (list_area.width, 0)
+            };
+        let title_area = Rect::new(list_area.x, list_area.y, title_width, list_area.height);
         let metadata_area = Rect::new(
-            list_area.x + list_area.width - metadata_width,
+            list_area.x + list_area.width.saturating_sub(metadata_width),
             list_area.y + 1,
             metadata_width,
-            list_area.height - 1,
+            list_area.height.saturating_sub(1),
         );
 
+        let title = format!(
+            "Videos (sort: {}, group: {})",
+            video_list.sort_mode().label(),
+            video_list.group_mode().label()
+        );
         let list = video_list.list(list_area.height.into());
         let styled_list = list
-            .block(Block::default().title("Videos"))
+            .block(Block::default().title(title))
             .style(Style::default().fg(Color::White));
 
         let metadata_list = video_list.metadata_list(list_area.height.into());
@@ -219,10 +491,51 @@ impl Component for FeedView {
 
         f.render_widget(styled_list, title_area);
         f.render_widget(styled_metadata_list, metadata_area);
-        f.render_widget(description, description_area);
+
+        match self.thumbnail {
+            Some(ref mut thumbnail) => {
+                let thumbnail_height = description_height - 1;
+                let thumbnail_width =
+                    Thumbnail::width_for_height(thumbnail_height).min(description_area.width / 2);
+                let thumbnail_area = Rect::new(
+                    description_area.x,
+                    description_area.y + 1,
+                    thumbnail_width,
+                    thumbnail_height,
+                );
+                let text_area = Rect::new(
+                    description_area.x + thumbnail_width + 1,
+                    description_area.y,
+                    description_area.width - thumbnail_width - 1,
+                    description_height,
+                );
+
+                thumbnail.set_url(video_list.current_thumbnail());
+                thumbnail.draw(f, thumbnail_area);
+                self.description.draw(f, text_area);
+            }
+            None => self.description.draw(f, description_area),
+        }
+
+        if let Some((_, ref mut dialog)) = self.dialog {
+            dialog.draw(f, area);
+        }
     }
 
     fn handle_event(&mut self, event: Event, size: Option<Size>) {
+        if self.dialog.is_some() {
+            self.handle_dialog_event(event, size);
+            return;
+        }
+
+        if self.description.is_focused() {
+            self.description.handle_event(event, size);
+            if let Some(start) = self.description.take_requested_start() {
+                self.play_current(Some(start));
+            }
+            return;
+        }
+
         let height = size.map_or(30, |size| size.height.into());
 
         if let Event::Key(event) = event {
@@ -237,11 +550,22 @@ impl Component for FeedView {
                 KeyCode::Char('G') => self.video_list.lock().move_bottom(),
                 KeyCode::Char('a') => self.video_list.lock().deselect_all(),
                 KeyCode::Char(' ') => self.video_list.lock().toggle_current(),
-                KeyCode::Enter => self.play(),
-                KeyCode::Char('p') => self.play_current(),
+                KeyCode::Enter => self.play(None),
+                KeyCode::Char('P') => self.open_player_dialog(),
+                KeyCode::Char('p') => self.play_current(None),
                 KeyCode::Char('y') => self.copy_current(),
+                KeyCode::Char('Y') => self.copy_selected(),
+                KeyCode::Char('o') => self.open_current(false),
+                KeyCode::Char('O') => self.open_current(true),
+                KeyCode::Char('w') => self.toggle_watch_later(),
+                KeyCode::Char('*') => self.toggle_starred(),
+                KeyCode::Char('D') => self.download_selected(),
+                KeyCode::Char('e') => self.open_export_dialog(),
                 KeyCode::Char('n') => self.set_current_as_last_played(),
                 KeyCode::Char('r') => self.backend.refetch(),
+                KeyCode::Char('s') => self.video_list.lock().cycle_sort_mode(),
+                KeyCode::Char('S') => self.video_list.lock().cycle_group_mode(),
+                KeyCode::Char('i') => self.description.focus(),
                 _ => return,
             }
         }
@@ -250,6 +574,14 @@ impl Component for FeedView {
     }
 
     fn registered_events(&self) -> Vec<(String, String)> {
+        if let Some((_, ref dialog)) = self.dialog {
+            return dialog.registered_events();
+        }
+
+        if self.description.is_focused() {
+            return self.description.registered_events();
+        }
+
         vec![
             (String::from("j"), String::from("Down")),
             (String::from("k"), String::from("Up")),
@@ -260,10 +592,21 @@ impl Component for FeedView {
             (String::from("Space"), String::from("Select")),
             (String::from("Enter"), String::from("Play")),
             (String::from("p"), String::from("Play current")),
+            (String::from("P"), String::from("Play with")),
             (String::from("y"), String::from("Copy url")),
+            (String::from("Y"), String::from("Copy selected")),
+            (String::from("o"), String::from("Open in browser")),
+            (String::from("O"), String::from("Open channel")),
+            (String::from("w"), String::from("Watch later")),
+            (String::from("*"), String::from("Star")),
+            (String::from("D"), String::from("Download")),
+            (String::from("e"), String::from("Export playlist")),
             (String::from("n"), String::from("Update last played")),
             (String::from("a"), String::from("Deselect all")),
             (String::from("r"), String::from("Reload")),
+            (String::from("s"), String::from("Sort")),
+            (String::from("S"), String::from("Group")),
+            (String::from("i"), String::from("Description")),
         ]
     }
 }
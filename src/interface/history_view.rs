@@ -0,0 +1,275 @@
+use super::{
+    actions::Actions,
+    component::{Component, Frame},
+    list::{List, Same},
+    player_launcher::PlayerLauncher,
+    selection_dialog::{Selection, SelectionDialog},
+};
+use crate::{
+    backend::channel::BackendMessage,
+    config::ConfigHandler,
+    history::{HistoryEntry, HistoryHandler},
+};
+
+use chrono::{DateTime, Duration, Local};
+use crossterm::event::{Event, KeyCode};
+use parking_lot::Mutex;
+use ratatui::{
+    layout::{Rect, Size},
+    style::{Color, Style},
+    widgets::{Block, ListItem},
+};
+use std::{cmp::Ordering, sync::Arc};
+
+#[derive(Clone)]
+struct HistoryItem(HistoryEntry);
+
+impl From<HistoryItem> for ListItem<'static> {
+    fn from(value: HistoryItem) -> Self {
+        let HistoryItem(entry) = value;
+        ListItem::new(format!(
+            " {}  {} - {}",
+            entry.timestamp.format("%Y-%m-%d %H:%M"),
+            entry.title,
+            entry.author
+        ))
+    }
+}
+
+impl Same for HistoryItem {
+    fn same(&self, other: &Self) -> bool {
+        self.0.timestamp == other.0.timestamp && self.0.url == other.0.url
+    }
+}
+
+// Newest first
+impl Ord for HistoryItem {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .0
+            .timestamp
+            .cmp(&self.0.timestamp)
+            .then_with(|| self.0.url.cmp(&other.0.url))
+    }
+}
+
+impl PartialOrd for HistoryItem {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for HistoryItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for HistoryItem {}
+
+#[derive(Clone, Copy)]
+enum ClearRange {
+    LastHour,
+    Today,
+    LastWeek,
+    LastMonth,
+    OlderThanMonth,
+    Everything,
+}
+
+impl ClearRange {
+    const ALL: [ClearRange; 6] = [
+        ClearRange::LastHour,
+        ClearRange::Today,
+        ClearRange::LastWeek,
+        ClearRange::LastMonth,
+        ClearRange::OlderThanMonth,
+        ClearRange::Everything,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::LastHour => "Last hour",
+            Self::Today => "Today",
+            Self::LastWeek => "Last 7 days",
+            Self::LastMonth => "Last 30 days",
+            Self::OlderThanMonth => "Older than 30 days",
+            Self::Everything => "Everything",
+        }
+    }
+
+    fn bounds(self) -> (Option<DateTime<Local>>, Option<DateTime<Local>>) {
+        let now = Local::now();
+        match self {
+            Self::LastHour => (Some(now - Duration::hours(1)), None),
+            Self::Today => {
+                let midnight = now
+                    .date_naive()
+                    .and_hms_opt(0, 0, 0)
+                    .and_then(|midnight| midnight.and_local_timezone(Local).earliest());
+                (midnight.or(Some(now - Duration::days(1))), None)
+            }
+            Self::LastWeek => (Some(now - Duration::days(7)), None),
+            Self::LastMonth => (Some(now - Duration::days(30)), None),
+            Self::OlderThanMonth => (None, Some(now - Duration::days(30))),
+            Self::Everything => (None, None),
+        }
+    }
+}
+
+pub struct HistoryView {
+    actions: Actions,
+    history: Arc<HistoryHandler>,
+    launcher: PlayerLauncher,
+    config: Arc<ConfigHandler>,
+    list: Arc<Mutex<List<HistoryItem>>>,
+    clear_dialog: Option<SelectionDialog>,
+}
+
+impl HistoryView {
+    pub fn new(
+        actions: Actions,
+        history: Arc<HistoryHandler>,
+        launcher: PlayerLauncher,
+        config: Arc<ConfigHandler>,
+    ) -> Self {
+        let history_view = Self {
+            actions,
+            history,
+            launcher,
+            config,
+            list: Arc::new(Mutex::new(List::new())),
+            clear_dialog: None,
+        };
+
+        history_view.listen_history_messages();
+        history_view
+    }
+
+    fn listen_history_messages(&self) {
+        let actions = self.actions.clone();
+        let list = self.list.clone();
+        let mut receiver = self.history.subscribe();
+        tokio::spawn(async move {
+            while let Some(message) = receiver.recv().await {
+                match message {
+                    BackendMessage::New(entry) => list.lock().add(HistoryItem(entry)),
+                    BackendMessage::Remove(entry) => list.lock().remove(&HistoryItem(entry)),
+                    _ => continue,
+                }
+                actions.redraw_async().await;
+            }
+        });
+    }
+
+    // The clear dialog handles all keys while it's open
+    pub fn is_capturing_keys(&self) -> bool {
+        self.clear_dialog.is_some()
+    }
+
+    fn open_clear_dialog(&mut self) {
+        let ranges = ClearRange::ALL.map(|range| String::from(range.label()));
+        self.clear_dialog = Some(SelectionDialog::new("Clear history", ranges.to_vec()));
+    }
+
+    fn handle_clear_dialog_event(&mut self, event: Event, size: Option<Size>) {
+        let Some(ref mut dialog) = self.clear_dialog else {
+            return;
+        };
+
+        dialog.handle_event(event, size);
+        match dialog.selection() {
+            Selection::Pending => (),
+            Selection::Cancelled => self.clear_dialog = None,
+            Selection::Selected(index) => {
+                self.clear_dialog = None;
+                self.clear(ClearRange::ALL[index]);
+            }
+        }
+
+        self.actions.redraw();
+    }
+
+    fn clear(&self, range: ClearRange) {
+        let (from, to) = range.bounds();
+        let history = self.history.clone();
+        let actions = self.actions.clone();
+        tokio::spawn(async move {
+            let clear_result = history.clear(from, to).await;
+            actions.redraw_or_error_async(clear_result, true).await;
+        });
+    }
+
+    fn replay_current(&mut self) {
+        if let Some(HistoryItem(entry)) = self.list.lock().get_current_item() {
+            self.launcher.replay(&entry);
+        }
+    }
+
+    fn copy_current(&mut self) {
+        if let Some(HistoryItem(entry)) = self.list.lock().get_current_item() {
+            self.actions
+                .copy_to_clipboard(self.config.clipboard(), entry.url.clone());
+        }
+    }
+}
+
+impl Component for HistoryView {
+    fn draw(&mut self, f: &mut Frame, area: Rect) {
+        {
+            let list = self.list.lock();
+            let title = format!("History ({})", list.iter().count());
+            let list = list
+                .list(area.height.into())
+                .block(Block::default().title(title))
+                .style(Style::default().fg(Color::White));
+            f.render_widget(list, area);
+        }
+
+        if let Some(ref mut dialog) = self.clear_dialog {
+            dialog.draw(f, area);
+        }
+    }
+
+    fn handle_event(&mut self, event: Event, size: Option<Size>) {
+        if self.clear_dialog.is_some() {
+            self.handle_clear_dialog_event(event, size);
+            return;
+        }
+
+        let height = size.map_or(30, |size| size.height.into());
+
+        let Event::Key(event) = event else {
+            return;
+        };
+
+        match event.code {
+            KeyCode::Up | KeyCode::Char('k') => self.list.lock().move_up(1),
+            KeyCode::Down | KeyCode::Char('j') => self.list.lock().move_down(1),
+            KeyCode::Char('d') => self.list.lock().move_down(height / 2),
+            KeyCode::Char('u') => self.list.lock().move_up(height / 2),
+            KeyCode::Char('g') => self.list.lock().move_top(),
+            KeyCode::Char('G') => self.list.lock().move_bottom(),
+            KeyCode::Enter | KeyCode::Char('p') => self.replay_current(),
+            KeyCode::Char('y') => self.copy_current(),
+            KeyCode::Char('X') => self.open_clear_dialog(),
+            _ => return,
+        }
+
+        self.actions.redraw();
+    }
+
+    fn registered_events(&self) -> Vec<(String, String)> {
+        if let Some(ref dialog) = self.clear_dialog {
+            return dialog.registered_events();
+        }
+
+        vec![
+            (String::from("j"), String::from("Down")),
+            (String::from("k"), String::from("Up")),
+            (String::from("Enter"), String::from("Replay")),
+            (String::from("y"), String::from("Copy url")),
+            (String::from("X"), String::from("Clear history")),
+        ]
+    }
+}
@@ -30,7 +30,10 @@ impl<T: Clone + Ord + Same + Into<ListItem<'static>>> List<T> {
     pub fn remove(&mut self, item_to_remove: &T) {
         self.items
             .mutate_vec(|items| items.retain(|item| !item.same(item_to_remove)));
-        self.current_index = self.items.first().map(|_| 0);
+        self.current_index = match self.items.len() {
+            0 => None,
+            length => self.current_index.map(|index| index.min(length - 1)),
+        };
     }
 
     pub fn clear(&mut self) {
@@ -54,14 +57,18 @@ impl<T: Clone + Ord + Same + Into<ListItem<'static>>> List<T> {
         self.mutate_current_index(|_| usize::MAX);
     }
 
+    pub fn move_to(&mut self, predicate: impl Fn(&T) -> bool) {
+        if let Some(index) = self.items.iter().position(predicate) {
+            self.current_index = Some(index);
+        }
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = &T> {
         self.items.iter()
     }
 
     pub fn get_current_item(&self) -> Option<T> {
-        let Some(current_index) = self.current_index else {
-            return None;
-        };
+        let current_index = self.current_index?;
         self.items.get(current_index).cloned()
     }
 
@@ -90,6 +97,53 @@ impl<T: Clone + Ord + Same + Into<ListItem<'static>>> List<T> {
         ListWidget::new(items)
     }
 
+    // Renders the list with a header row inserted before every new group. The headers aren't
+    // selectable and are skipped when moving the current index.
+    pub fn map_grouped_list<F, G, H, R>(
+        &self,
+        height: usize,
+        group: G,
+        header: H,
+        f: F,
+    ) -> ListWidget<'_>
+    where
+        R: Into<ListItem<'static>>,
+        F: Fn(T) -> R,
+        G: Fn(&T) -> String,
+        H: Fn(String) -> ListItem<'static>,
+    {
+        let Some(current_index) = self.current_index else {
+            return ListWidget::new(Vec::<ListItem>::new());
+        };
+
+        let mut rows: Vec<ListItem<'_>> = vec![];
+        let mut current_row = 0;
+        let mut previous_group = None;
+        for (i, item) in self.items.iter().cloned().enumerate() {
+            let item_group = group(&item);
+            if previous_group.as_ref() != Some(&item_group) {
+                rows.push(header(item_group.clone()));
+                previous_group = Some(item_group);
+            }
+
+            let row: ListItem<'_> = f(item).into();
+            if i == current_index {
+                current_row = rows.len();
+                rows.push(row.style(Style::default().fg(Color::Green)));
+            } else {
+                rows.push(row);
+            }
+        }
+
+        let range = Self::list_range(height, rows.len(), current_row);
+        let rows: Vec<ListItem<'_>> = rows
+            .into_iter()
+            .skip(range.start)
+            .take(range.len())
+            .collect();
+        ListWidget::new(rows)
+    }
+
     fn map_visible_items<R, F>(&self, height: usize, f: F) -> Vec<ListItem<'_>>
     where
         R: Into<ListItem<'static>>,
@@ -1,41 +1,158 @@
 use super::{
     actions::Actions,
     backend::rss_view::RssBackendView,
+    backend_provider::Handlers,
     component::{Component, Frame},
+    config_watcher::ConfigWatcher,
+    downloads_view::DownloadsView,
     feed_view::FeedView,
+    history_view::HistoryView,
+    remote_server::RemoteServer,
+    starred_view::StarredView,
+    watch_later_view::WatchLaterView,
 };
 use crate::backend::rss::RssBackend;
-use crate::config::ConfigHandler;
 
 use crossterm::event::{Event, KeyCode};
 use parking_lot::Mutex;
-use ratatui::layout::{Constraint, Direction, Layout, Rect, Size};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect, Size},
+    style::{Color, Style},
+    widgets::Tabs,
+};
 use std::sync::Arc;
 
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+enum Tab {
+    #[default]
+    Feed,
+    WatchLater,
+    Starred,
+    History,
+    Downloads,
+}
+
+impl Tab {
+    const ALL: [Tab; 5] = [
+        Tab::Feed,
+        Tab::WatchLater,
+        Tab::Starred,
+        Tab::History,
+        Tab::Downloads,
+    ];
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|tab| *tab == self).unwrap_or(0)
+    }
+
+    fn next(self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+
+    fn previous(self) -> Self {
+        Self::ALL[(self.index() + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Feed => "Feed",
+            Self::WatchLater => "Watch later",
+            Self::Starred => "Starred",
+            Self::History => "History",
+            Self::Downloads => "Downloads",
+        }
+    }
+}
+
 pub struct MainView {
     actions: Actions,
 
     show_backend_view: Arc<Mutex<bool>>,
+    tab: Tab,
 
     feed: FeedView,
+    watch_later: WatchLaterView,
+    starred: StarredView,
+    history: HistoryView,
+    downloads: DownloadsView,
     backend_view: RssBackendView,
 }
 
 impl MainView {
-    pub fn new(actions: Actions, config: ConfigHandler, backend: Arc<RssBackend>) -> Self {
+    pub fn new(actions: Actions, handlers: Handlers, backend: Arc<RssBackend>) -> Self {
+        let feed = FeedView::new(actions.clone(), &handlers, backend.clone());
+        let watch_later = WatchLaterView::new(
+            actions.clone(),
+            handlers.watch_later,
+            feed.launcher(),
+            handlers.config.clone(),
+        );
+        let starred = StarredView::new(
+            actions.clone(),
+            handlers.starred,
+            feed.launcher(),
+            handlers.config.clone(),
+        );
+        let history = HistoryView::new(
+            actions.clone(),
+            handlers.history,
+            feed.launcher(),
+            handlers.config.clone(),
+        );
+        let downloads =
+            DownloadsView::new(actions.clone(), handlers.downloads.clone(), feed.launcher());
+        RemoteServer::start(actions.clone(), backend.clone(), feed.launcher());
+        ConfigWatcher::start(
+            actions.clone(),
+            handlers.config.clone(),
+            backend.clone(),
+            handlers.downloads.clone(),
+        );
+
         Self {
             show_backend_view: Arc::new(Mutex::new(false)),
-            feed: FeedView::new(actions.clone(), config, backend.clone()),
+            tab: Tab::default(),
+            feed,
+            watch_later,
+            starred,
+            history,
+            downloads,
             backend_view: RssBackendView::new(actions.clone(), backend),
             actions,
         }
     }
+
+    fn current_tab(&mut self) -> &mut dyn Component {
+        match self.tab {
+            Tab::Feed => &mut self.feed,
+            Tab::WatchLater => &mut self.watch_later,
+            Tab::Starred => &mut self.starred,
+            Tab::History => &mut self.history,
+            Tab::Downloads => &mut self.downloads,
+        }
+    }
+
+    fn is_capturing_keys(&self) -> bool {
+        match self.tab {
+            Tab::Feed => self.feed.is_capturing_keys(),
+            Tab::WatchLater | Tab::Downloads => false,
+            Tab::Starred => self.starred.is_capturing_keys(),
+            Tab::History => self.history.is_capturing_keys(),
+        }
+    }
 }
 
 impl Component for MainView {
     fn draw(&mut self, f: &mut Frame, area: Rect) {
-        let show_backend_view = self.show_backend_view.lock();
-        let backend_view_numerator = u32::from(*show_backend_view);
+        let tabs = Tabs::new(Tab::ALL.map(Tab::label))
+            .select(self.tab.index())
+            .style(Style::default().fg(Color::White))
+            .highlight_style(Style::default().fg(Color::Green));
+        f.render_widget(tabs, Rect::new(area.x, area.y, area.width, 1));
+        let area = Rect::new(area.x, area.y + 1, area.width, area.height - 1);
+
+        let show_backend_view = *self.show_backend_view.lock();
+        let backend_view_numerator = u32::from(show_backend_view);
 
         let chunks = Layout::default()
             .direction(Direction::Horizontal)
@@ -48,15 +165,17 @@ impl Component for MainView {
             )
             .split(area);
 
-        if *show_backend_view {
+        if show_backend_view {
             self.backend_view.draw(f, chunks[0]);
         }
 
-        self.feed.draw(f, chunks[1]);
+        self.current_tab().draw(f, chunks[1]);
     }
 
     fn handle_event(&mut self, event: Event, size: Option<Size>) {
-        if *self.show_backend_view.lock() {
+        if self.is_capturing_keys() {
+            self.current_tab().handle_event(event, size);
+        } else if *self.show_backend_view.lock() {
             if matches!(event, Event::Key(event) if event.code == KeyCode::Esc) {
                 let mut show_backend_view = self.show_backend_view.lock();
                 *show_backend_view = false;
@@ -70,8 +189,14 @@ impl Component for MainView {
                 *show_backend_view = true;
             }
             self.actions.redraw();
+        } else if matches!(event, Event::Key(event) if event.code == KeyCode::Tab) {
+            self.tab = self.tab.next();
+            self.actions.redraw();
+        } else if matches!(event, Event::Key(event) if event.code == KeyCode::BackTab) {
+            self.tab = self.tab.previous();
+            self.actions.redraw();
         } else {
-            self.feed.handle_event(event, size);
+            self.current_tab().handle_event(event, size);
         }
     }
 
@@ -81,8 +206,17 @@ impl Component for MainView {
             events.append(&mut self.backend_view.registered_events());
             events
         } else {
-            let mut events = vec![(String::from("c"), String::from("Configure"))];
-            events.append(&mut self.feed.registered_events());
+            let mut events = vec![
+                (String::from("c"), String::from("Configure")),
+                (String::from("Tab"), String::from("Next tab")),
+            ];
+            events.append(&mut match self.tab {
+                Tab::Feed => self.feed.registered_events(),
+                Tab::WatchLater => self.watch_later.registered_events(),
+                Tab::Starred => self.starred.registered_events(),
+                Tab::History => self.history.registered_events(),
+                Tab::Downloads => self.downloads.registered_events(),
+            });
             events
         }
     }
@@ -6,12 +6,23 @@ mod actions;
 pub mod app;
 
 mod backend_provider;
+mod config_watcher;
+mod description;
 mod dialog;
+mod downloads_view;
 mod error_handler;
 mod feed_view;
+mod history_view;
 mod list;
 mod main_view;
+mod overlay;
+mod player_launcher;
+mod remote_server;
+mod selection_dialog;
+mod starred_view;
 mod status_label;
+mod thumbnail;
 mod video_list;
+mod watch_later_view;
 
 mod component;
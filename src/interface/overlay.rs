@@ -0,0 +1,111 @@
+use parking_lot::Mutex;
+use ratatui::{buffer::Buffer, layout::Rect};
+use std::sync::Arc;
+
+// Escape sequence that draws on top of an area of the terminal, such as an image. Overlays are
+// written after the buffer has been drawn since the cells of the buffer only hold text of a known
+// width.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Overlay {
+    pub area: Rect,
+    pub sequence: Arc<str>,
+    // Written once the overlay is no longer drawn, for overlays that aren't removed by drawing text
+    // on top of them
+    pub clear: Option<Arc<str>>,
+}
+
+// Overlays drawn during the current draw
+#[derive(Clone, Default)]
+pub struct Overlays {
+    pending: Arc<Mutex<Vec<Overlay>>>,
+    // Sequences that don't draw anything, such as setting the clipboard, which are written once
+    // after the next frame
+    sequences: Arc<Mutex<Vec<String>>>,
+}
+
+impl Overlays {
+    // Skips the cells of the area when drawing the buffer so that they aren't drawn on top of the
+    // overlay
+    pub fn draw(&self, buffer: &mut Buffer, overlay: Overlay) {
+        let area = overlay.area.intersection(buffer.area);
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                buffer[(x, y)].set_skip(true);
+            }
+        }
+
+        self.pending.lock().push(overlay);
+    }
+
+    pub fn write(&self, sequence: String) {
+        self.sequences.lock().push(sequence);
+    }
+
+    pub fn take_sequences(&self) -> Vec<String> {
+        std::mem::take(&mut *self.sequences.lock())
+    }
+
+    // Returns the overlays drawn since the last call, except the ones that something else has been
+    // drawn on top of
+    pub fn take_visible(&self, buffer: &Buffer) -> Vec<Overlay> {
+        let pending = std::mem::take(&mut *self.pending.lock());
+        pending
+            .into_iter()
+            .filter(|overlay| {
+                let area = overlay.area.intersection(buffer.area);
+                !area.is_empty() && area.positions().all(|position| buffer[position].skip)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Style;
+
+    fn overlay(area: Rect) -> Overlay {
+        Overlay {
+            area,
+            sequence: "\x1b]8;;https://example.com\x1b\\https://example.com\x1b]8;;\x1b\\".into(),
+            clear: None,
+        }
+    }
+
+    #[test]
+    fn skips_cells_of_overlays() {
+        let overlays = Overlays::default();
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 30, 2));
+        // Links of odd width followed by text
+        buffer.set_string(0, 0, "https://example.com and more", Style::default());
+        overlays.draw(&mut buffer, overlay(Rect::new(0, 0, 19, 1)));
+
+        assert!((0..19).all(|x| buffer[(x, 0)].skip));
+        assert!((19..30).all(|x| !buffer[(x, 0)].skip));
+        assert_eq!(overlays.take_visible(&buffer).len(), 1);
+        assert!(overlays.take_visible(&buffer).is_empty());
+    }
+
+    #[test]
+    fn hides_overlays_that_are_drawn_over() {
+        let overlays = Overlays::default();
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 30, 2));
+        overlays.draw(&mut buffer, overlay(Rect::new(0, 0, 19, 1)));
+        overlays.draw(&mut buffer, overlay(Rect::new(0, 1, 19, 1)));
+        buffer[(5, 1)].reset();
+
+        assert_eq!(
+            overlays.take_visible(&buffer),
+            vec![overlay(Rect::new(0, 0, 19, 1))]
+        );
+    }
+
+    #[test]
+    fn hides_overlays_outside_of_the_buffer() {
+        let overlays = Overlays::default();
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 30, 2));
+        overlays.draw(&mut buffer, overlay(Rect::new(0, 5, 19, 1)));
+
+        assert!(overlays.take_visible(&buffer).is_empty());
+    }
+}
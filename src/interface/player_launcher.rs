@@ -0,0 +1,286 @@
+use super::{actions::Actions, backend_provider::Handlers, video_list::VideoList};
+use crate::{
+    backend::{Video, rss::RssBackend},
+    config::ConfigHandler,
+    download::DownloadManager,
+    history::{HistoryEntry, HistoryHandler},
+    hooks::HookEvent,
+    mpv,
+    playback::{PlaybackHandler, Progress},
+    player::{self, PlayerError},
+};
+
+use parking_lot::Mutex;
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+
+const PLAYING_STRING: &str = "Playing...";
+
+// Starts players for videos, tracks the playback when enabled and keeps track of the mpv instance
+// that videos are queued to
+#[derive(Clone)]
+pub struct PlayerLauncher {
+    actions: Actions,
+    config: Arc<ConfigHandler>,
+    playback: Arc<PlaybackHandler>,
+    history: Arc<HistoryHandler>,
+    downloads: Arc<DownloadManager>,
+    backend: Arc<RssBackend>,
+    video_list: Arc<Mutex<VideoList>>,
+    // IPC socket and player template of the running mpv instance when in queue mode
+    queue: Arc<Mutex<Option<(PathBuf, String)>>>,
+    // Urls of the videos that are played from their downloaded files, by the paths of the files.
+    // Shared by all players since videos can be queued to an instance that was started earlier
+    local_urls: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl PlayerLauncher {
+    pub fn new(
+        actions: Actions,
+        handlers: &Handlers,
+        backend: Arc<RssBackend>,
+        video_list: Arc<Mutex<VideoList>>,
+    ) -> Self {
+        Self {
+            actions,
+            config: handlers.config.clone(),
+            playback: handlers.playback.clone(),
+            history: handlers.history.clone(),
+            downloads: handlers.downloads.clone(),
+            backend,
+            video_list,
+            queue: Default::default(),
+            local_urls: Default::default(),
+        }
+    }
+
+    // Plays the videos in order, optionally with the provided player profile instead of the
+    // configured ones and from the provided offset in seconds
+    pub fn play(&self, videos: Vec<Video>, profile: Option<&str>, start: Option<u32>) {
+        let groups = self.group_by_player(videos, profile);
+        let launcher = self.clone();
+        tokio::spawn(async move {
+            launcher.launch(groups, start).await;
+        });
+    }
+
+    // Plays the video of the history entry again with the player it was played with
+    pub fn replay(&self, entry: &HistoryEntry) {
+        let groups = vec![(entry.player.clone(), vec![entry.video()])];
+        let launcher = self.clone();
+        tokio::spawn(async move {
+            launcher.launch(groups, None).await;
+        });
+    }
+
+    pub fn update_last_played_timestamp(&self, last_played_timestamp: i64) {
+        let launcher = self.clone();
+        tokio::spawn(async move {
+            launcher
+                .save_last_played_timestamp(last_played_timestamp)
+                .await;
+        });
+    }
+
+    async fn save_last_played_timestamp(&self, last_played_timestamp: i64) {
+        {
+            self.video_list
+                .lock()
+                .update_last_played_timestamp(last_played_timestamp);
+        }
+
+        self.actions
+            .redraw_or_error_async(
+                self.config
+                    .set_last_played_timestamp(last_played_timestamp)
+                    .await,
+                true,
+            )
+            .await;
+    }
+
+    // Groups the videos by the player template they should be played with while keeping the order
+    fn group_by_player(
+        &self,
+        videos: Vec<Video>,
+        profile: Option<&str>,
+    ) -> Vec<(String, Vec<Video>)> {
+        let mut groups: Vec<(String, Vec<Video>)> = vec![];
+        for video in videos {
+            let template = self.player_template(&video, profile);
+            match groups
+                .iter_mut()
+                .find(|(existing, _)| *existing == template)
+            {
+                Some((_, videos)) => videos.push(video),
+                None => groups.push((template, vec![video])),
+            }
+        }
+        groups
+    }
+
+    // Whether the video is marked as watched once played, rather than by the last played timestamp
+    // when starting to play it
+    pub fn is_tracked(&self, video: &Video, profile: Option<&str>) -> bool {
+        self.config.playback_tracking().is_some()
+            && player::is_mpv_template(&self.player_template(video, profile))
+    }
+
+    // The player is picked from the provided profile, the command line, the feed of the video or
+    // the config, in that order
+    fn player_template(&self, video: &Video, profile: Option<&str>) -> String {
+        let player = profile
+            .map(str::to_owned)
+            .or_else(|| self.config.player_override())
+            .or_else(|| self.backend.feed_player(&video.feed_url))
+            .unwrap_or_else(|| self.config.player());
+        self.config.player_template(&player)
+    }
+
+    // Plays the groups of videos one after another with the player template of each group
+    async fn launch(&self, groups: Vec<(String, Vec<Video>)>, start: Option<u32>) {
+        let status_id = self.actions.start_status(PLAYING_STRING);
+        let mut watched = vec![];
+        let mut play_result = Ok(());
+        for (template, videos) in groups {
+            let entries = videos
+                .iter()
+                .map(|video| HistoryEntry::new(video, &template))
+                .collect();
+            if let Err(error) = self.history.record(entries).await {
+                self.actions.handle_error_async(error, true).await;
+            }
+            for video in &videos {
+                self.run_play_hook(video.clone(), template.clone());
+            }
+
+            play_result = self
+                .play_group(&template, &videos, start, status_id)
+                .await
+                .map(|mut urls| watched.append(&mut urls));
+            if play_result.is_err() {
+                break;
+            }
+        }
+        self.actions.finish_status(status_id);
+
+        if !watched.is_empty() {
+            self.video_list.lock().set_watched(watched);
+        }
+
+        self.actions.redraw_or_error_async(play_result, true).await;
+    }
+
+    fn run_play_hook(&self, video: Video, player: String) {
+        let hooks = self.config.hooks();
+        let actions = self.actions.clone();
+        tokio::spawn(async move {
+            let result = hooks.run(HookEvent::Play { video, player }).await;
+            actions.handle_result_async(result, true).await;
+        });
+    }
+
+    // Plays the videos and returns the urls of the videos that were watched, which are only known
+    // when tracking playback
+    async fn play_group(
+        &self,
+        template: &str,
+        videos: &[Video],
+        start: Option<u32>,
+        status_id: usize,
+    ) -> Result<Vec<String>, PlayerError> {
+        // Downloaded videos are played from their local files, which are mapped back to the urls
+        // of the videos when tracking playback
+        let videos: Vec<Video> = videos
+            .iter()
+            .map(|video| match self.downloads.local_path(&video.url) {
+                Some(path) => {
+                    let path = path.to_string_lossy().into_owned();
+                    self.local_urls
+                        .lock()
+                        .insert(path.clone(), video.url.clone());
+                    Video {
+                        url: path,
+                        ..video.clone()
+                    }
+                }
+                None => video.clone(),
+            })
+            .collect();
+        let videos = &videos[..];
+        let url = |path: &str| {
+            self.local_urls
+                .lock()
+                .get(path)
+                .cloned()
+                .unwrap_or_else(|| path.to_owned())
+        };
+
+        let tracking = self.config.playback_tracking();
+        let queue = self.config.queue();
+        if !player::is_mpv_template(template) || (tracking.is_none() && !queue) {
+            player::play(template, videos, start).await?;
+            return Ok(vec![]);
+        }
+
+        // Only videos played from the beginning with the same player are queued since mpv is
+        // already running with the arguments of its template, the others get their own instance
+        let queued = self.queue.lock().clone();
+        if let Some((socket, queued_template)) = queued
+            && queue
+            && queued_template == template
+            && start.is_none()
+        {
+            return player::enqueue(&socket, videos).await.map(|_| vec![]);
+        }
+
+        // Videos are only queued once mpv accepts connections, and to the first running instance
+        let socket = mpv::socket_path().await?;
+        let on_connect = || {
+            let mut queued = self.queue.lock();
+            if queue && queued.is_none() {
+                *queued = Some((socket.clone(), template.to_owned()));
+            }
+        };
+
+        // Playing from a chapter takes precedence over resuming
+        let resume = |path: &str| match (tracking, start) {
+            (Some(_), None) => self.playback.position(&url(path)),
+            _ => None,
+        };
+        let on_queue_change = |length| {
+            self.actions
+                .update_status(status_id, &format!("{PLAYING_STRING} ({length} in queue)"));
+        };
+        let progress = player::play_with_ipc(
+            template,
+            videos,
+            start,
+            &socket,
+            on_connect,
+            resume,
+            on_queue_change,
+        )
+        .await;
+
+        {
+            let mut queued = self.queue.lock();
+            if queued.as_ref().is_some_and(|(queued, _)| *queued == socket) {
+                *queued = None;
+            }
+        }
+
+        match tracking {
+            Some(threshold) => {
+                let progress = progress?
+                    .into_iter()
+                    .map(|progress| Progress {
+                        url: url(&progress.url),
+                        ..progress
+                    })
+                    .collect();
+                Ok(self.playback.record(progress, threshold).await?)
+            }
+            None => progress.map(|_| vec![]),
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use super::{actions::Actions, player_launcher::PlayerLauncher};
+use crate::{
+    backend::{Backend, Video, rss::RssBackend},
+    remote::{self, RemoteCommand, RemoteError, RemoteResponse},
+};
+
+use log::warn;
+use std::sync::Arc;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{UnixListener, UnixStream},
+};
+
+// Runs the commands sent through the remote socket, such as the ones of `tuibe remote`
+#[derive(Clone)]
+pub struct RemoteServer {
+    actions: Actions,
+    backend: Arc<RssBackend>,
+    launcher: PlayerLauncher,
+}
+
+impl RemoteServer {
+    pub fn start(actions: Actions, backend: Arc<RssBackend>, launcher: PlayerLauncher) {
+        let server = Self {
+            actions,
+            backend,
+            launcher,
+        };
+
+        tokio::spawn(async move {
+            match remote::listen().await {
+                Ok(listener) => server.accept(listener).await,
+                Err(error) => server.actions.handle_error_async(error, true).await,
+            }
+        });
+    }
+
+    async fn accept(&self, listener: UnixListener) {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    let server = self.clone();
+                    tokio::spawn(async move {
+                        if let Err(error) = server.handle_connection(stream).await {
+                            warn!("Remote connection failed: {}", error);
+                        }
+                    });
+                }
+                Err(error) => warn!("Failed to accept remote connection: {}", error),
+            }
+        }
+    }
+
+    // Every line is a command which is answered with a line containing the result
+    async fn handle_connection(&self, stream: UnixStream) -> Result<(), RemoteError> {
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        while let Some(line) = lines.next_line().await? {
+            let command = serde_json::from_str::<RemoteCommand>(&line);
+            let result = match command {
+                Ok(ref command) => self.run(command).await,
+                Err(ref error) => Err(error.to_string()),
+            };
+
+            let mut response = serde_json::to_string(&RemoteResponse {
+                error: result.err(),
+            })?;
+            response.push('\n');
+            writer.write_all(response.as_bytes()).await?;
+
+            // Quitting last lets the response be sent first
+            if let Ok(RemoteCommand::Quit) = command {
+                self.actions.quit_async().await;
+            }
+        }
+        Ok(())
+    }
+
+    async fn run(&self, command: &RemoteCommand) -> Result<(), String> {
+        match command {
+            RemoteCommand::Add { url } => self
+                .backend
+                .add_feed(url)
+                .await
+                .map_err(|error| error.to_string())?,
+            RemoteCommand::Remove { url } => self
+                .backend
+                .remove_feed(url)
+                .await
+                .map_err(|error| error.to_string())?,
+            RemoteCommand::Refresh => self.backend.refetch(),
+            RemoteCommand::Queue { url } => {
+                let video = self
+                    .backend
+                    .video(url)
+                    .unwrap_or_else(|| Video::from_url(url));
+                self.launcher.play(vec![video], None, None);
+            }
+            RemoteCommand::Quit => return Ok(()),
+        }
+
+        self.actions.redraw_async().await;
+        Ok(())
+    }
+}
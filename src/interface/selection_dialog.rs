@@ -0,0 +1,111 @@
+use crate::interface::component::{Component, Frame};
+
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    layout::{Rect, Size},
+    style::{Color, Style},
+    widgets::{Block, Borders, Clear, List, ListItem},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Selection {
+    Pending,
+    Selected(usize),
+    Cancelled,
+}
+
+// A dialog for picking one of the provided options. The owner is responsible for checking the
+// selection after passing events to it.
+pub struct SelectionDialog {
+    title: String,
+    options: Vec<String>,
+    current_index: usize,
+    selection: Selection,
+}
+
+impl SelectionDialog {
+    pub fn new(title: &str, options: Vec<String>) -> Self {
+        Self {
+            title: title.to_owned(),
+            options,
+            current_index: 0,
+            selection: Selection::Pending,
+        }
+    }
+
+    pub fn selection(&self) -> Selection {
+        self.selection
+    }
+
+    pub fn option(&self, index: usize) -> Option<&str> {
+        self.options.get(index).map(String::as_str)
+    }
+}
+
+impl Component for SelectionDialog {
+    fn draw(&mut self, f: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .title(self.title.as_str())
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::White))
+            .style(Style::default().bg(Color::Black));
+
+        let items: Vec<ListItem> = self
+            .options
+            .iter()
+            .enumerate()
+            .map(|(i, option)| {
+                let item = ListItem::new(format!(" {option}"));
+                if i == self.current_index {
+                    item.style(Style::default().fg(Color::Green))
+                } else {
+                    item
+                }
+            })
+            .collect();
+        let list = List::new(items)
+            .block(block)
+            .style(Style::default().fg(Color::White));
+
+        let width = 40.min(area.width);
+        let height = u16::try_from(self.options.len() + 2)
+            .unwrap_or(u16::MAX)
+            .min(area.height);
+        let area = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+
+        f.render_widget(Clear, area);
+        f.render_widget(list, area);
+    }
+
+    fn handle_event(&mut self, event: Event, _size: Option<Size>) {
+        let Event::Key(event) = event else {
+            return;
+        };
+
+        match event.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.current_index = self.current_index.saturating_sub(1)
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.current_index = (self.current_index + 1).min(self.options.len() - 1)
+            }
+            KeyCode::Enter => self.selection = Selection::Selected(self.current_index),
+            KeyCode::Esc => self.selection = Selection::Cancelled,
+            _ => (),
+        }
+    }
+
+    fn registered_events(&self) -> Vec<(String, String)> {
+        vec![
+            (String::from("j"), String::from("Down")),
+            (String::from("k"), String::from("Up")),
+            (String::from("Enter"), String::from("Select")),
+            (String::from("Esc"), String::from("Cancel")),
+        ]
+    }
+}
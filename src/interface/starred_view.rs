@@ -0,0 +1,287 @@
+use super::{
+    actions::Actions,
+    component::{Component, Frame},
+    list::{List, Same},
+    player_launcher::PlayerLauncher,
+};
+use crate::{
+    backend::{Video, channel::BackendMessage},
+    config::ConfigHandler,
+    starred::StarredHandler,
+};
+
+use crossterm::event::{Event, KeyCode};
+use parking_lot::Mutex;
+use ratatui::{
+    layout::{Rect, Size},
+    style::{Color, Style},
+    widgets::{Block, ListItem},
+};
+use std::{cmp::Ordering, sync::Arc};
+
+#[derive(Clone)]
+struct StarredItem(Video);
+
+impl From<StarredItem> for ListItem<'static> {
+    fn from(value: StarredItem) -> Self {
+        let StarredItem(video) = value;
+        ListItem::new(format!(
+            " {} - {} ({})",
+            video.title,
+            video.author,
+            video.date.format("%Y-%m-%d")
+        ))
+    }
+}
+
+impl Same for StarredItem {
+    fn same(&self, other: &Self) -> bool {
+        self.0.url == other.0.url
+    }
+}
+
+// Newest first
+impl Ord for StarredItem {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .0
+            .date
+            .cmp(&self.0.date)
+            .then_with(|| self.0.url.cmp(&other.0.url))
+    }
+}
+
+impl PartialOrd for StarredItem {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for StarredItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for StarredItem {}
+
+// All starred videos together with the list of the ones matching the search query
+struct StarredList {
+    videos: Vec<Video>,
+    list: List<StarredItem>,
+    query: String,
+}
+
+impl StarredList {
+    fn matches(&self, video: &Video) -> bool {
+        let query = self.query.to_lowercase();
+        video.title.to_lowercase().contains(&query) || video.author.to_lowercase().contains(&query)
+    }
+
+    fn add(&mut self, video: Video) {
+        if self.matches(&video) {
+            self.list.add(StarredItem(video.clone()));
+        }
+        self.videos.push(video);
+    }
+
+    fn remove(&mut self, video: Video) {
+        self.videos.retain(|existing| existing.url != video.url);
+        self.list.remove(&StarredItem(video));
+    }
+
+    fn set_query(&mut self, query: String) {
+        let current_url = self.list.get_current_item().map(|item| item.0.url);
+
+        self.query = query;
+        self.list.clear();
+        for video in self.videos.clone() {
+            if self.matches(&video) {
+                self.list.add(StarredItem(video));
+            }
+        }
+
+        if let Some(current_url) = current_url {
+            self.list.move_to(|item| item.0.url == current_url);
+        }
+    }
+}
+
+pub struct StarredView {
+    actions: Actions,
+    starred: Arc<StarredHandler>,
+    launcher: PlayerLauncher,
+    config: Arc<ConfigHandler>,
+    list: Arc<Mutex<StarredList>>,
+    searching: bool,
+}
+
+impl StarredView {
+    pub fn new(
+        actions: Actions,
+        starred: Arc<StarredHandler>,
+        launcher: PlayerLauncher,
+        config: Arc<ConfigHandler>,
+    ) -> Self {
+        let starred_view = Self {
+            actions,
+            starred,
+            launcher,
+            config,
+            list: Arc::new(Mutex::new(StarredList {
+                videos: vec![],
+                list: List::new(),
+                query: String::new(),
+            })),
+            searching: false,
+        };
+
+        starred_view.listen_starred_messages();
+        starred_view
+    }
+
+    fn listen_starred_messages(&self) {
+        let actions = self.actions.clone();
+        let list = self.list.clone();
+        let mut receiver = self.starred.subscribe();
+        tokio::spawn(async move {
+            while let Some(message) = receiver.recv().await {
+                match message {
+                    BackendMessage::New(video) => list.lock().add(video),
+                    BackendMessage::Remove(video) => list.lock().remove(video),
+                    _ => continue,
+                }
+                actions.redraw_async().await;
+            }
+        });
+    }
+
+    // Keys are handled by the search field while searching
+    pub fn is_capturing_keys(&self) -> bool {
+        self.searching
+    }
+
+    fn set_searching(&mut self, searching: bool) {
+        self.searching = searching;
+        self.actions.set_text_input(searching);
+    }
+
+    fn handle_search_event(&mut self, code: KeyCode) {
+        let mut query = self.list.lock().query.clone();
+        match code {
+            KeyCode::Enter => return self.set_searching(false),
+            KeyCode::Esc => {
+                query.clear();
+                self.set_searching(false);
+            }
+            KeyCode::Backspace => {
+                query.pop();
+            }
+            KeyCode::Char(character) => query.push(character),
+            _ => return,
+        }
+        self.list.lock().set_query(query);
+    }
+
+    fn current_video(&self) -> Option<Video> {
+        self.list.lock().list.get_current_item().map(|item| item.0)
+    }
+
+    fn play_current(&mut self) {
+        if let Some(video) = self.current_video() {
+            self.launcher.play(vec![video], None, None);
+        }
+    }
+
+    fn unstar_current(&mut self) {
+        let Some(video) = self.current_video() else {
+            return;
+        };
+
+        let starred = self.starred.clone();
+        let actions = self.actions.clone();
+        tokio::spawn(async move {
+            let result = starred.toggle(video).await;
+            actions.redraw_or_error_async(result, true).await;
+        });
+    }
+
+    fn copy_current(&mut self) {
+        if let Some(video) = self.current_video() {
+            self.actions
+                .copy_to_clipboard(self.config.clipboard(), video.url.clone());
+        }
+    }
+}
+
+impl Component for StarredView {
+    fn draw(&mut self, f: &mut Frame, area: Rect) {
+        let starred_list = self.list.lock();
+        let count = starred_list.list.iter().count();
+        let title = match (self.searching, starred_list.query.as_str()) {
+            (false, "") => format!("Starred ({count})"),
+            (searching, query) => format!(
+                "Starred ({count}/{}) /{query}{}",
+                starred_list.videos.len(),
+                if searching { "_" } else { "" }
+            ),
+        };
+
+        let list = starred_list
+            .list
+            .list(area.height.into())
+            .block(Block::default().title(title))
+            .style(Style::default().fg(Color::White));
+        f.render_widget(list, area);
+    }
+
+    fn handle_event(&mut self, event: Event, size: Option<Size>) {
+        let height = size.map_or(30, |size| size.height.into());
+
+        let Event::Key(event) = event else {
+            return;
+        };
+
+        if self.searching {
+            self.handle_search_event(event.code);
+            self.actions.redraw();
+            return;
+        }
+
+        match event.code {
+            KeyCode::Up | KeyCode::Char('k') => self.list.lock().list.move_up(1),
+            KeyCode::Down | KeyCode::Char('j') => self.list.lock().list.move_down(1),
+            KeyCode::Char('d') => self.list.lock().list.move_down(height / 2),
+            KeyCode::Char('u') => self.list.lock().list.move_up(height / 2),
+            KeyCode::Char('g') => self.list.lock().list.move_top(),
+            KeyCode::Char('G') => self.list.lock().list.move_bottom(),
+            KeyCode::Char('/') => self.set_searching(true),
+            KeyCode::Esc => self.list.lock().set_query(String::new()),
+            KeyCode::Enter | KeyCode::Char('p') => self.play_current(),
+            KeyCode::Char('x') | KeyCode::Char('*') => self.unstar_current(),
+            KeyCode::Char('y') => self.copy_current(),
+            _ => return,
+        }
+
+        self.actions.redraw();
+    }
+
+    fn registered_events(&self) -> Vec<(String, String)> {
+        if self.searching {
+            return vec![
+                (String::from("Enter"), String::from("Search")),
+                (String::from("Esc"), String::from("Cancel")),
+            ];
+        }
+
+        vec![
+            (String::from("j"), String::from("Down")),
+            (String::from("k"), String::from("Up")),
+            (String::from("/"), String::from("Search")),
+            (String::from("Esc"), String::from("Clear search")),
+            (String::from("Enter"), String::from("Play")),
+            (String::from("x"), String::from("Unstar")),
+            (String::from("y"), String::from("Copy url")),
+        ]
+    }
+}
@@ -0,0 +1,359 @@
+use super::{
+    actions::Actions,
+    component::{Component, Frame},
+    overlay::Overlay,
+};
+use crate::{config::ThumbnailMode, file_handler};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use image::{DynamicImage, RgbImage, imageops::FilterType};
+use log::warn;
+use parking_lot::Mutex;
+use ratatui::{buffer::Buffer, layout::Rect, style::Color};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+    sync::Arc,
+};
+use thiserror::Error;
+use tokio::fs;
+
+const KITTY_CHUNK_SIZE: usize = 4096;
+const KITTY_CLEAR: &str = "\x1b_Ga=d,d=A,q=2\x1b\\";
+// Used when the terminal doesn't report its size in pixels
+const DEFAULT_CELL_SIZE: (u16, u16) = (8, 16);
+
+#[derive(Debug, Error)]
+enum ThumbnailError {
+    #[error("Failed to find cache dir")]
+    FindCacheDir,
+
+    #[error("Failed to download thumbnail: {}", _0)]
+    Download(#[from] reqwest::Error),
+
+    #[error("Failed to cache thumbnail: {}", _0)]
+    Cache(#[from] std::io::Error),
+
+    #[error("Failed to decode thumbnail: {}", _0)]
+    Decode(#[from] image::ImageError),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsProtocol {
+    Kitty,
+    Sixel,
+    HalfBlocks,
+}
+
+impl GraphicsProtocol {
+    pub fn from_mode(mode: ThumbnailMode) -> Option<Self> {
+        match mode {
+            ThumbnailMode::Off => None,
+            ThumbnailMode::Kitty => Some(Self::Kitty),
+            ThumbnailMode::Sixel => Some(Self::Sixel),
+            ThumbnailMode::HalfBlocks => Some(Self::HalfBlocks),
+            ThumbnailMode::Auto => Some(Self::detect()),
+        }
+    }
+
+    fn detect() -> Self {
+        let var = |name| std::env::var(name).unwrap_or_default();
+        let term = var("TERM");
+        let term_program = var("TERM_PROGRAM");
+
+        // Escape sequences would need to be wrapped to pass through tmux and screen
+        if !var("TMUX").is_empty() || term.starts_with("screen") {
+            Self::HalfBlocks
+        } else if !var("KITTY_WINDOW_ID").is_empty()
+            || term == "xterm-kitty"
+            || term == "xterm-ghostty"
+            || term_program == "WezTerm"
+            || term_program == "ghostty"
+        {
+            Self::Kitty
+        } else if ["foot", "mlterm", "yaft", "contour"]
+            .iter()
+            .any(|name| term.starts_with(name))
+        {
+            Self::Sixel
+        } else {
+            Self::HalfBlocks
+        }
+    }
+}
+
+#[derive(Clone)]
+enum ThumbnailState {
+    Loading,
+    Loaded(Arc<DynamicImage>),
+    Failed,
+}
+
+pub struct Thumbnail {
+    actions: Actions,
+    protocol: GraphicsProtocol,
+    url: Option<String>,
+    images: Arc<Mutex<HashMap<String, ThumbnailState>>>,
+    // Encoding is too slow to do on every draw, the last encoded image is therefore kept
+    encoded: Option<(String, Rect, Arc<str>)>,
+}
+
+impl Thumbnail {
+    pub fn new(actions: Actions, protocol: GraphicsProtocol) -> Self {
+        Self {
+            actions,
+            protocol,
+            url: None,
+            images: Default::default(),
+            encoded: None,
+        }
+    }
+
+    pub fn set_url(&mut self, url: Option<String>) {
+        self.url = url;
+    }
+
+    // Width in cells of a 16:9 thumbnail with the provided height
+    pub fn width_for_height(height: u16) -> u16 {
+        let (cell_width, cell_height) = Self::cell_size();
+        let width = f32::from(height) * f32::from(cell_height) * 16.0 / 9.0;
+        (width / f32::from(cell_width)).ceil() as u16
+    }
+
+    fn cell_size() -> (u16, u16) {
+        crossterm::terminal::window_size()
+            .ok()
+            .filter(|size| size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0)
+            .map(|size| (size.width / size.columns, size.height / size.rows))
+            .unwrap_or(DEFAULT_CELL_SIZE)
+    }
+
+    fn image(&self, url: &str) -> Option<Arc<DynamicImage>> {
+        let mut images = self.images.lock();
+        match images.get(url) {
+            Some(ThumbnailState::Loaded(image)) => Some(image.clone()),
+            Some(ThumbnailState::Loading | ThumbnailState::Failed) => None,
+            None => {
+                images.insert(url.to_owned(), ThumbnailState::Loading);
+                self.load(url.to_owned());
+                None
+            }
+        }
+    }
+
+    fn load(&self, url: String) {
+        let actions = self.actions.clone();
+        let images = self.images.clone();
+        tokio::spawn(async move {
+            let state = match Self::load_impl(&url).await {
+                Ok(image) => ThumbnailState::Loaded(Arc::new(image)),
+                Err(error) => {
+                    warn!("Failed to load thumbnail {}: {}", url, error);
+                    ThumbnailState::Failed
+                }
+            };
+
+            {
+                images.lock().insert(url, state);
+            }
+            actions.redraw_async().await;
+        });
+    }
+
+    async fn load_impl(url: &str) -> Result<DynamicImage, ThumbnailError> {
+        let path = Self::cache_path(url)?;
+        if let Ok(bytes) = fs::read(&path).await {
+            match image::load_from_memory(&bytes) {
+                Ok(image) => return Ok(image),
+                // Files that were only partially written are downloaded again
+                Err(error) => {
+                    warn!("Removing cached thumbnail {}: {}", path.display(), error);
+                    fs::remove_file(&path).await?;
+                }
+            }
+        }
+
+        let bytes = reqwest::get(url).await?.error_for_status()?.bytes().await?;
+        let image = image::load_from_memory(&bytes)?;
+        fs::create_dir_all(path.parent().ok_or(ThumbnailError::FindCacheDir)?).await?;
+        fs::write(&path, &bytes).await?;
+        Ok(image)
+    }
+
+    // The file name is an FNV-1a hash of the url, which unlike the hashers of std stays the same
+    // between Rust versions
+    fn cache_path(url: &str) -> Result<PathBuf, ThumbnailError> {
+        let hash = url.bytes().fold(0xcbf29ce484222325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+        });
+
+        let mut path = file_handler::find_cache_dir().map_err(|_| ThumbnailError::FindCacheDir)?;
+        path.push("thumbnails");
+        path.push(format!("{hash:016x}"));
+        Ok(path)
+    }
+
+    fn encode(&mut self, url: &str, area: Rect, image: &DynamicImage) -> Arc<str> {
+        if let Some((encoded_url, encoded_area, encoded)) = &self.encoded
+            && encoded_url == url
+            && *encoded_area == area
+        {
+            return encoded.clone();
+        }
+
+        let (cell_width, cell_height) = Self::cell_size();
+        let image = image
+            .resize(
+                u32::from(area.width) * u32::from(cell_width),
+                u32::from(area.height) * u32::from(cell_height),
+                FilterType::Triangle,
+            )
+            .to_rgb8();
+
+        let encoded: Arc<str> = match self.protocol {
+            GraphicsProtocol::Kitty => {
+                let columns = image.width().div_ceil(u32::from(cell_width));
+                let rows = image.height().div_ceil(u32::from(cell_height));
+                Self::encode_kitty(&image, columns, rows).into()
+            }
+            GraphicsProtocol::Sixel => Self::encode_sixel(&image).into(),
+            GraphicsProtocol::HalfBlocks => "".into(),
+        };
+
+        self.encoded = Some((url.to_owned(), area, encoded.clone()));
+        encoded
+    }
+
+    fn encode_kitty(image: &RgbImage, columns: u32, rows: u32) -> String {
+        let data = BASE64.encode(image.as_raw());
+        let chunks: Vec<&[u8]> = data.as_bytes().chunks(KITTY_CHUNK_SIZE).collect();
+
+        let mut encoded = String::from(KITTY_CLEAR);
+        for (i, chunk) in chunks.iter().enumerate() {
+            let more = u8::from(i + 1 < chunks.len());
+            let chunk = String::from_utf8_lossy(chunk);
+            if i == 0 {
+                encoded.push_str(&format!(
+                    "\x1b_Ga=T,f=24,s={},v={},c={},r={},C=1,q=2,m={more};{chunk}\x1b\\",
+                    image.width(),
+                    image.height(),
+                    columns,
+                    rows,
+                ));
+            } else {
+                encoded.push_str(&format!("\x1b_Gm={more};{chunk}\x1b\\"));
+            }
+        }
+
+        encoded
+    }
+
+    // Encodes the image using a 6x6x6 color cube as the palette
+    fn encode_sixel(image: &RgbImage) -> String {
+        let (width, height) = image.dimensions();
+        let mut encoded = format!("\x1bP0;1;0q\"1;1;{width};{height}");
+        for color in 0..216 {
+            let (r, g, b) = (color / 36, color / 6 % 6, color % 6);
+            encoded.push_str(&format!("#{color};2;{};{};{}", r * 20, g * 20, b * 20));
+        }
+
+        let palette_index = |pixel: &image::Rgb<u8>| {
+            let [r, g, b] = pixel
+                .0
+                .map(|channel| (usize::from(channel) * 5 + 127) / 255);
+            r * 36 + g * 6 + b
+        };
+
+        for band in (0..height).step_by(6) {
+            let mut colors: BTreeMap<usize, Vec<u8>> = BTreeMap::new();
+            for x in 0..width {
+                for row in 0..6.min(height - band) {
+                    let color = palette_index(image.get_pixel(x, band + row));
+                    colors
+                        .entry(color)
+                        .or_insert_with(|| vec![0; width as usize])[x as usize] |= 1 << row;
+                }
+            }
+
+            for (color, sixels) in colors {
+                encoded.push_str(&format!("#{color}"));
+                let mut sixels = sixels.into_iter().peekable();
+                while let Some(sixel) = sixels.next() {
+                    let mut count = 1;
+                    while sixels.next_if_eq(&sixel).is_some() {
+                        count += 1;
+                    }
+
+                    let character = char::from(63 + sixel);
+                    if count > 3 {
+                        encoded.push_str(&format!("!{count}{character}"));
+                    } else {
+                        encoded.extend(std::iter::repeat_n(character, count));
+                    }
+                }
+                encoded.push('$');
+            }
+            encoded.push('-');
+        }
+
+        encoded.push_str("\x1b\\");
+        encoded
+    }
+
+    fn render_half_blocks(buffer: &mut Buffer, area: Rect, image: &DynamicImage) {
+        let image = image
+            .resize(
+                u32::from(area.width),
+                u32::from(area.height) * 2,
+                FilterType::Triangle,
+            )
+            .to_rgb8();
+
+        let color = |x, y| {
+            let [r, g, b] = image.get_pixel(x, y).0;
+            Color::Rgb(r, g, b)
+        };
+
+        for y in 0..image.height().div_ceil(2) {
+            for x in 0..image.width() {
+                let top = color(x, y * 2);
+                let bottom = color(x, (y * 2 + 1).min(image.height() - 1));
+                // The image is never larger than the area and the coordinates therefore fit in u16
+                buffer[(area.x + x as u16, area.y + y as u16)]
+                    .set_symbol("▀")
+                    .set_fg(top)
+                    .set_bg(bottom);
+            }
+        }
+    }
+}
+
+impl Component for Thumbnail {
+    fn draw(&mut self, f: &mut Frame, area: Rect) {
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+
+        let Some(url) = self.url.clone() else {
+            return;
+        };
+        let Some(image) = self.image(&url) else {
+            return;
+        };
+
+        match self.protocol {
+            GraphicsProtocol::HalfBlocks => Self::render_half_blocks(f.buffer_mut(), area, &image),
+            GraphicsProtocol::Kitty | GraphicsProtocol::Sixel => {
+                // Kitty images aren't removed when drawing text on top of them, and are deleted once
+                // the thumbnail is no longer drawn
+                let clear = (self.protocol == GraphicsProtocol::Kitty).then(|| KITTY_CLEAR.into());
+                let overlay = Overlay {
+                    area,
+                    sequence: self.encode(&url, area, &image),
+                    clear,
+                };
+                self.actions.draw_overlay(f.buffer_mut(), overlay);
+            }
+        }
+    }
+}
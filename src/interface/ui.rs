@@ -1,11 +1,14 @@
-use std::time::Duration;
+use std::{io::Write, time::Duration};
 
-use super::component::{Backend, Component};
+use super::{
+    component::{Backend, Component},
+    overlay::{Overlay, Overlays},
+};
 
-use crossterm::event::EventStream;
+use crossterm::{cursor::MoveTo, event::EventStream, queue};
 use futures_timer::Delay;
 use log::{debug, info};
-use ratatui::Terminal;
+use ratatui::{Terminal, layout::Rect};
 use thiserror::Error;
 use tokio::select;
 use tokio_stream::StreamExt;
@@ -33,12 +36,14 @@ pub async fn create<T: Component, F>(
     creator: F,
 ) -> Result<(), UiError>
 where
-    F: FnOnce(flume::Sender<UiMessage>) -> T,
+    F: FnOnce(flume::Sender<UiMessage>, Overlays) -> T,
 {
     let mut event_reader = EventStream::new();
     let (ui_sender, ui_receiver) = flume::unbounded();
+    let overlays = Overlays::default();
+    let mut written_overlays = WrittenOverlays::default();
 
-    let mut root = creator(ui_sender.clone());
+    let mut root = creator(ui_sender.clone(), overlays.clone());
     ui_sender.send_async(UiMessage::Redraw).await?;
 
     info!("Starting event loop");
@@ -65,7 +70,7 @@ where
                     }
 
                     debug!("Redrawing");
-                    perform_draw(terminal, &mut root)?;
+                    perform_draw(terminal, &mut root, &overlays, &mut written_overlays)?;
                 }
             },
         };
@@ -80,10 +85,49 @@ async fn delayed_recv<T>(receiver: &flume::Receiver<T>, delay: u64) -> Result<T,
     receiver.recv_async().await
 }
 
+// Overlays that are on the screen, which are only written again once they change
+#[derive(Default)]
+struct WrittenOverlays {
+    area: Rect,
+    overlays: Vec<Overlay>,
+}
+
 fn perform_draw<T: Component>(
     terminal: &mut Terminal<Backend>,
     root: &mut T,
+    overlays: &Overlays,
+    written: &mut WrittenOverlays,
 ) -> Result<(), UiError> {
-    terminal.draw(|f| root.draw(f, f.area()))?;
+    let (area, visible) = {
+        let frame = terminal.draw(|f| root.draw(f, f.area()))?;
+        (frame.area, overlays.take_visible(frame.buffer))
+    };
+
+    // The screen is cleared when the terminal is resized
+    if area != written.area {
+        written.area = area;
+        written.overlays.clear();
+    }
+
+    let backend = terminal.backend_mut();
+    for overlay in written.overlays.iter() {
+        if let Some(ref clear) = overlay.clear
+            && !visible.contains(overlay)
+        {
+            backend.write_all(clear.as_bytes())?;
+        }
+    }
+    for overlay in visible.iter() {
+        if !written.overlays.contains(overlay) {
+            queue!(backend, MoveTo(overlay.area.x, overlay.area.y))?;
+            backend.write_all(overlay.sequence.as_bytes())?;
+        }
+    }
+    for sequence in overlays.take_sequences() {
+        backend.write_all(sequence.as_bytes())?;
+    }
+    backend.flush()?;
+
+    written.overlays = visible;
     Ok(())
 }
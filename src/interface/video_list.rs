@@ -1,24 +1,113 @@
 use super::list::{List, Same};
-use crate::backend::{channel::BackendMessage, Video};
+use crate::backend::{Video, VideoKind, channel::BackendMessage};
 
-use chrono::{DateTime, FixedOffset};
+use chrono::{DateTime, FixedOffset, Local};
 use delegate::delegate;
 use ratatui::{
-    style::{Color, Style},
-    widgets::{Block, Borders, List as ListWidget, ListItem, Paragraph, Wrap},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{List as ListWidget, ListItem},
 };
-use std::cmp::Reverse;
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+};
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortMode {
+    #[default]
+    Newest,
+    Oldest,
+    Channel,
+    Duration,
+    Unwatched,
+}
+
+impl SortMode {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Newest => Self::Oldest,
+            Self::Oldest => Self::Channel,
+            Self::Channel => Self::Duration,
+            Self::Duration => Self::Unwatched,
+            Self::Unwatched => Self::Newest,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Newest => "newest",
+            Self::Oldest => "oldest",
+            Self::Channel => "channel",
+            Self::Duration => "duration",
+            Self::Unwatched => "unwatched",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum GroupMode {
+    #[default]
+    None,
+    Day,
+    Channel,
+}
+
+impl GroupMode {
+    pub fn next(self) -> Self {
+        match self {
+            Self::None => Self::Day,
+            Self::Day => Self::Channel,
+            Self::Channel => Self::None,
+        }
+    }
 
-#[derive(Clone, Ord, PartialOrd, Eq, PartialEq)]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Day => "day",
+            Self::Channel => "channel",
+        }
+    }
+}
+
+// Headers of the day groups, from the most recent
+const DAY_GROUPS: [&str; 5] = ["Today", "Yesterday", "This week", "This month", "Older"];
+
+#[derive(Clone)]
 pub struct VideoListItem {
-    video: Reverse<Video>,
+    video: Video,
     selected: bool,
+    unwatched: bool,
+    starred: bool,
+    sort_mode: SortMode,
+    group_mode: GroupMode,
+    // Keys that the items are compared by, which are computed when the video or the modes change
+    // rather than on every comparison
+    day_group: usize,
+    author_key: String,
 }
 
 impl From<VideoListItem> for ListItem<'static> {
     fn from(value: VideoListItem) -> Self {
         let selected = if value.selected { "✓" } else { " " };
-        ListItem::new(format!(" {selected} {}", value.video.0.title))
+        let badge = match value.video.kind {
+            VideoKind::Regular => None,
+            VideoKind::Short => Some(Span::styled("[SHORT] ", Style::default().fg(Color::Cyan))),
+            VideoKind::Live => Some(Span::styled("[LIVE] ", Style::default().fg(Color::Red))),
+            VideoKind::Upcoming => Some(Span::styled(
+                "[UPCOMING] ",
+                Style::default().fg(Color::Yellow),
+            )),
+        };
+
+        let mut spans = vec![Span::raw(format!(" {selected} "))];
+        if value.starred {
+            spans.push(Span::styled("★ ", Style::default().fg(Color::Yellow)));
+        }
+        spans.extend(badge);
+        spans.push(Span::raw(value.video.title));
+        ListItem::new(Line::from(spans))
     }
 }
 
@@ -30,17 +119,81 @@ impl From<Video> for VideoListItem {
 
 impl Same for VideoListItem {
     fn same(&self, other: &Self) -> bool {
-        self.video.0.url == other.video.0.url
+        self.video.url == other.video.url
+    }
+}
+
+// The items are ordered by their group first to keep the groups together, then by the sort mode and
+// lastly newest first. The url is compared last to keep the ordering total since the list
+// deduplicates equal items.
+impl Ord for VideoListItem {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let group_ordering = match self.group_mode {
+            GroupMode::None => Ordering::Equal,
+            GroupMode::Day => self.day_group.cmp(&other.day_group),
+            GroupMode::Channel => self
+                .author_key
+                .cmp(&other.author_key)
+                .then_with(|| self.video.author.cmp(&other.video.author)),
+        };
+
+        let ordering = match self.sort_mode {
+            SortMode::Newest => Ordering::Equal,
+            SortMode::Oldest => self.video.date.cmp(&other.video.date),
+            SortMode::Channel => self.author_key.cmp(&other.author_key),
+            SortMode::Duration => self.video.length.cmp(&other.video.length),
+            SortMode::Unwatched => other.unwatched.cmp(&self.unwatched),
+        };
+
+        group_ordering
+            .then(ordering)
+            .then_with(|| other.video.date.cmp(&self.video.date))
+            .then_with(|| self.video.title.cmp(&other.video.title))
+            .then_with(|| self.video.url.cmp(&other.video.url))
+    }
+}
+
+impl PartialOrd for VideoListItem {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
+impl PartialEq for VideoListItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for VideoListItem {}
+
 impl VideoListItem {
     pub fn new(video: Video, last_played_timestamp: i64) -> Self {
-        let selected = video.date.timestamp() > last_played_timestamp;
-        Self {
-            video: Reverse(video),
-            selected,
-        }
+        let unwatched = video.date.timestamp() > last_played_timestamp;
+        let mut item = Self {
+            video,
+            selected: unwatched,
+            unwatched,
+            starred: false,
+            sort_mode: SortMode::default(),
+            group_mode: GroupMode::default(),
+            day_group: 0,
+            author_key: String::new(),
+        };
+        item.update_keys();
+        item
+    }
+
+    fn set_video(&mut self, video: Video) {
+        self.video = video;
+        self.update_keys();
+    }
+
+    // The day group is also updated when the group mode changes, since it depends on the current
+    // date
+    fn update_keys(&mut self) {
+        self.day_group = self.compute_day_group();
+        self.author_key = self.video.author.to_lowercase();
     }
 
     pub fn toggle_selected(&mut self) {
@@ -52,31 +205,97 @@ impl VideoListItem {
     }
 
     pub fn select_based_on_timestamp(&mut self, last_played_timestamp: i64) {
-        self.selected = self.date().timestamp() > last_played_timestamp;
+        self.unwatched = self.date().timestamp() > last_played_timestamp;
+        self.selected = self.unwatched;
+    }
+
+    fn mark_watched(&mut self) {
+        self.unwatched = false;
+        self.selected = false;
+    }
+
+    fn group(&self) -> String {
+        match self.group_mode {
+            GroupMode::None => String::new(),
+            GroupMode::Channel => self.author(),
+            GroupMode::Day => String::from(DAY_GROUPS[self.day_group]),
+        }
+    }
+
+    // Index of the day group in `DAY_GROUPS`
+    fn compute_day_group(&self) -> usize {
+        let today = Local::now().date_naive();
+        let date = self.date().with_timezone(&Local).date_naive();
+        match (today - date).num_days() {
+            ..=0 => 0,
+            1 => 1,
+            2..7 => 2,
+            7..30 => 3,
+            _ => 4,
+        }
     }
 
     pub fn date(&self) -> DateTime<FixedOffset> {
-        self.video.0.date
+        self.video.date
     }
 
     pub fn author(&self) -> String {
-        self.video.0.author.clone()
+        self.video.author.clone()
+    }
+
+    pub fn length(&self) -> u32 {
+        self.video.length
+    }
+
+    pub fn views(&self) -> Option<u64> {
+        self.video.views
+    }
+
+    pub fn rating(&self) -> Option<f32> {
+        self.video.rating.as_ref().map(|rating| rating.average)
+    }
+
+    pub fn thumbnail(&self) -> Option<String> {
+        self.video.thumbnail.clone()
     }
 
     pub fn description(&self) -> String {
-        self.video.0.description.clone()
+        self.video.description.clone()
     }
 
     pub fn url(&self) -> String {
-        self.video.0.url.clone()
+        self.video.url.clone()
+    }
+
+    pub fn video(&self) -> Video {
+        self.video.clone()
     }
 }
 
-pub struct VideoList(List<VideoListItem>);
+pub struct VideoList {
+    list: List<VideoListItem>,
+    sort_mode: SortMode,
+    group_mode: GroupMode,
+    hidden_kinds: Vec<VideoKind>,
+    // Videos of the hidden kinds by their urls, which are added once their kinds are shown again
+    hidden: HashMap<String, Video>,
+    // Urls of starred videos, which are kept to mark videos that are added later on
+    starred: HashSet<String>,
+    // Urls of videos that playback tracking has marked as watched
+    watched: HashSet<String>,
+}
 
 impl VideoList {
-    pub fn new() -> Self {
-        Self(List::new())
+    pub fn new(hidden_kinds: Vec<VideoKind>) -> Self {
+        Self {
+            list: List::new(),
+            sort_mode: SortMode::default(),
+            group_mode: GroupMode::default(),
+            hidden_kinds,
+            hidden: HashMap::new(),
+            starred: HashSet::new(),
+            watched: HashSet::new(),
+        }
     }
 
     pub fn handle_backend_message(
@@ -85,78 +304,270 @@ impl VideoList {
         last_played_timestamp: i64,
     ) {
         match message {
-            BackendMessage::Clear => self.0.clear(),
-            BackendMessage::New(video) => {
-                let video_list_item = VideoListItem::new(video, last_played_timestamp);
-                self.0.add(video_list_item);
+            BackendMessage::Clear => {
+                self.list.clear();
+                self.hidden.clear();
+            }
+            BackendMessage::New(video) | BackendMessage::Update(video)
+                if self.hidden_kinds.contains(&video.kind) =>
+            {
+                self.list.remove(&video.clone().into());
+                self.hidden.insert(video.url.clone(), video);
+            }
+            BackendMessage::New(video) => self.add(video, last_played_timestamp),
+            // Videos that were hidden when they were added are added once they're updated to a
+            // kind that isn't hidden
+            BackendMessage::Update(video)
+                if !self.list.iter().any(|item| item.video.url == video.url) =>
+            {
+                self.hidden.remove(&video.url);
+                self.add(video, last_played_timestamp)
+            }
+            BackendMessage::Update(video) => self.list.mutate_every_item(|item| {
+                if item.video.url == video.url {
+                    item.set_video(video.clone());
+                }
+            }),
+            BackendMessage::Remove(video) => {
+                self.hidden.remove(&video.url);
+                self.list.remove(&video.into());
             }
-            BackendMessage::Remove(video) => self.0.remove(&video.into()),
             BackendMessage::FinishedFetching => (), // Handled by FeedView
             BackendMessage::Error(_) => (),         // Handled by FeedView
         }
     }
 
+    // Hides the videos of the kinds and shows the videos of the kinds that are no longer hidden
+    pub fn set_hidden_kinds(&mut self, hidden_kinds: Vec<VideoKind>, last_played_timestamp: i64) {
+        if hidden_kinds == self.hidden_kinds {
+            return;
+        }
+        self.hidden_kinds = hidden_kinds;
+
+        let hidden: Vec<Video> = self
+            .list
+            .iter()
+            .filter(|item| self.hidden_kinds.contains(&item.video.kind))
+            .map(|item| item.video())
+            .collect();
+        for video in hidden {
+            self.list.remove(&video.clone().into());
+            self.hidden.insert(video.url.clone(), video);
+        }
+
+        let shown: Vec<String> = self
+            .hidden
+            .values()
+            .filter(|video| !self.hidden_kinds.contains(&video.kind))
+            .map(|video| video.url.clone())
+            .collect();
+        for url in shown {
+            if let Some(video) = self.hidden.remove(&url) {
+                self.add(video, last_played_timestamp);
+            }
+        }
+    }
+
+    fn add(&mut self, video: Video, last_played_timestamp: i64) {
+        let mut video_list_item = VideoListItem::new(video, last_played_timestamp);
+        video_list_item.sort_mode = self.sort_mode;
+        video_list_item.group_mode = self.group_mode;
+        video_list_item.starred = self.starred.contains(&video_list_item.video.url);
+        if self.watched.contains(&video_list_item.video.url) {
+            video_list_item.mark_watched();
+        }
+        self.list.add(video_list_item);
+    }
+
     delegate! {
-        to self.0 {
+        to self.list {
             pub fn move_up(&mut self, steps: usize);
             pub fn move_down(&mut self, steps: usize);
             pub fn move_top(&mut self);
             pub fn move_bottom(&mut self);
-            pub fn list(&self, height: usize) -> ListWidget<'_>;
         }
     }
 
+    pub fn sort_mode(&self) -> SortMode {
+        self.sort_mode
+    }
+
+    pub fn group_mode(&self) -> GroupMode {
+        self.group_mode
+    }
+
+    pub fn cycle_sort_mode(&mut self) {
+        let sort_mode = self.sort_mode.next();
+        let current_url = self.list.get_current_item().map(|video| video.url());
+
+        self.sort_mode = sort_mode;
+        self.list
+            .mutate_every_item(|video| video.sort_mode = sort_mode);
+        if let Some(current_url) = current_url {
+            self.list.move_to(|video| video.url() == current_url);
+        }
+    }
+
+    pub fn set_starred(&mut self, url: &str, starred: bool) {
+        if starred {
+            self.starred.insert(url.to_owned());
+        } else {
+            self.starred.remove(url);
+        }
+
+        self.list.mutate_every_item(|video| {
+            if video.video.url == url {
+                video.starred = starred;
+            }
+        });
+    }
+
+    pub fn cycle_group_mode(&mut self) {
+        let group_mode = self.group_mode.next();
+        let current_url = self.list.get_current_item().map(|video| video.url());
+
+        self.group_mode = group_mode;
+        self.list.mutate_every_item(|video| {
+            video.group_mode = group_mode;
+            video.update_keys();
+        });
+        if let Some(current_url) = current_url {
+            self.list.move_to(|video| video.url() == current_url);
+        }
+    }
+
+    pub fn list(&self, height: usize) -> ListWidget<'_> {
+        self.map_list(
+            height,
+            |video| video,
+            |group| ListItem::new(group).style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+    }
+
     pub fn metadata_list(&self, height: usize) -> ListWidget<'_> {
-        self.0.map_list(height, |video| {
-            let author_width = 15;
-            let author = video.author();
-            let author = author.get(..author_width).unwrap_or(&author);
-            let date = video.date().format("%Y-%m-%d %H:%M");
+        self.map_list(
+            height,
+            |video| {
+                let author_width = 15;
+                let author = video.author();
+                let author = author.get(..author_width).unwrap_or(&author);
+                let length = format_length(video.length());
+                let views = video.views().map(format_count).unwrap_or_default();
+                let rating = video
+                    .rating()
+                    .map(|rating| format!("★{rating:.1}"))
+                    .unwrap_or_default();
+                let date = video.date().format("%Y-%m-%d %H:%M");
 
-            ListItem::new(format!("{author:>author_width$} - {date} "))
-        })
+                ListItem::new(format!(
+                    "{author:>author_width$} {length:>8} {views:>6} {rating:>4} - {date} "
+                ))
+            },
+            |_| ListItem::new(""),
+        )
+    }
+
+    fn map_list<F, R>(
+        &self,
+        height: usize,
+        f: F,
+        header: impl Fn(String) -> ListItem<'static>,
+    ) -> ListWidget<'_>
+    where
+        R: Into<ListItem<'static>>,
+        F: Fn(VideoListItem) -> R,
+    {
+        match self.group_mode {
+            GroupMode::None => self.list.map_list(height, f),
+            _ => self
+                .list
+                .map_grouped_list(height, |video| video.group(), header, f),
+        }
     }
 
     pub fn toggle_current(&mut self) {
-        self.0.mutate_current_item(|video| video.toggle_selected());
+        self.list
+            .mutate_current_item(|video| video.toggle_selected());
     }
 
     pub fn deselect_all(&mut self) {
-        self.0.mutate_every_item(|video| video.deselect());
+        self.list.mutate_every_item(|video| video.deselect());
     }
 
     pub fn current_timestamp(&self) -> Option<i64> {
-        self.0
+        self.list
             .get_current_item()
             .map(|item| item.date().timestamp())
     }
 
     pub fn update_last_played_timestamp(&mut self, last_played_timestamp: i64) {
-        self.0
-            .mutate_every_item(|video| video.select_based_on_timestamp(last_played_timestamp));
+        let watched = &self.watched;
+        self.list.mutate_every_item(|video| {
+            video.select_based_on_timestamp(last_played_timestamp);
+            if watched.contains(&video.video.url) {
+                video.mark_watched();
+            }
+        });
+    }
+
+    pub fn set_watched(&mut self, urls: Vec<String>) {
+        self.watched.extend(urls);
+        let watched = &self.watched;
+        self.list.mutate_every_item(|video| {
+            if watched.contains(&video.video.url) {
+                video.mark_watched();
+            }
+        });
     }
 
     pub fn selected_videos(&self) -> Vec<VideoListItem> {
-        self.0
+        self.list
             .iter()
             .cloned()
             .filter_map(|video| video.selected.then_some(video))
             .collect()
     }
 
+    pub fn unwatched_videos(&self) -> Vec<VideoListItem> {
+        self.list
+            .iter()
+            .filter(|video| video.unwatched)
+            .cloned()
+            .collect()
+    }
+
     pub fn current_video(&self) -> Option<VideoListItem> {
-        self.0.get_current_item()
+        self.list.get_current_item()
+    }
+
+    pub fn current_thumbnail(&self) -> Option<String> {
+        self.list
+            .get_current_item()
+            .and_then(|video| video.thumbnail())
     }
 
-    pub fn current_description(&self) -> Paragraph<'_> {
-        let description = self
-            .0
+    pub fn current_description(&self) -> String {
+        self.list
             .get_current_item()
             .map(|video| video.description())
-            .unwrap_or_default();
-        Paragraph::new(description)
-            .block(Block::default().title("Description").borders(Borders::TOP))
-            .style(Style::default().fg(Color::White))
-            .wrap(Wrap { trim: true })
+            .unwrap_or_default()
+    }
+}
+
+fn format_length(seconds: u32) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    match (hours, minutes, seconds) {
+        (0, 0, 0) => String::new(),
+        (0, _, _) => format!("{minutes}:{seconds:02}"),
+        _ => format!("{hours}:{minutes:02}:{seconds:02}"),
+    }
+}
+
+fn format_count(count: u64) -> String {
+    match count {
+        0..1_000 => count.to_string(),
+        1_000..1_000_000 => format!("{:.1}K", count as f64 / 1e3),
+        1_000_000..1_000_000_000 => format!("{:.1}M", count as f64 / 1e6),
+        _ => format!("{:.1}B", count as f64 / 1e9),
     }
 }
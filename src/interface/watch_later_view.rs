@@ -0,0 +1,231 @@
+use super::{
+    actions::Actions,
+    component::{Component, Frame},
+    list::{List, Same},
+    player_launcher::PlayerLauncher,
+};
+use crate::{
+    backend::channel::BackendMessage,
+    config::ConfigHandler,
+    watch_later::{WatchLaterHandler, WatchLaterItem},
+};
+
+use crossterm::event::{Event, KeyCode};
+use parking_lot::Mutex;
+use ratatui::{
+    layout::{Rect, Size},
+    style::{Color, Style},
+    widgets::{Block, ListItem},
+};
+use std::{cmp::Ordering, sync::Arc};
+
+impl From<WatchLaterItem> for ListItem<'static> {
+    fn from(value: WatchLaterItem) -> Self {
+        ListItem::new(format!(
+            " {:>3}. {} - {}",
+            value.position + 1,
+            value.video.title,
+            value.video.author
+        ))
+    }
+}
+
+impl Same for WatchLaterItem {
+    fn same(&self, other: &Self) -> bool {
+        self.video.url == other.video.url
+    }
+}
+
+impl Ord for WatchLaterItem {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.position
+            .cmp(&other.position)
+            .then_with(|| self.video.url.cmp(&other.video.url))
+    }
+}
+
+impl PartialOrd for WatchLaterItem {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for WatchLaterItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for WatchLaterItem {}
+
+pub struct WatchLaterView {
+    actions: Actions,
+    watch_later: Arc<WatchLaterHandler>,
+    launcher: PlayerLauncher,
+    config: Arc<ConfigHandler>,
+    list: Arc<Mutex<List<WatchLaterItem>>>,
+    // Url of the video that was last moved, which the cursor follows when the list is updated
+    moved_url: Arc<Mutex<Option<String>>>,
+}
+
+impl WatchLaterView {
+    pub fn new(
+        actions: Actions,
+        watch_later: Arc<WatchLaterHandler>,
+        launcher: PlayerLauncher,
+        config: Arc<ConfigHandler>,
+    ) -> Self {
+        let watch_later_view = Self {
+            actions,
+            watch_later,
+            launcher,
+            config,
+            list: Arc::new(Mutex::new(List::new())),
+            moved_url: Default::default(),
+        };
+
+        watch_later_view.listen_watch_later_messages();
+        watch_later_view
+    }
+
+    fn listen_watch_later_messages(&self) {
+        let actions = self.actions.clone();
+        let list = self.list.clone();
+        let moved_url = self.moved_url.clone();
+        let mut receiver = self.watch_later.subscribe();
+        tokio::spawn(async move {
+            while let Some(message) = receiver.recv().await {
+                {
+                    let mut list = list.lock();
+                    match message {
+                        BackendMessage::New(item) => list.add(item),
+                        BackendMessage::Update(item) => {
+                            // The cursor only follows the moved video to its new position once
+                            let moved = moved_url.lock().take_if(|url| *url == item.video.url);
+                            list.remove(&item);
+                            list.add(item);
+                            if let Some(url) = moved {
+                                list.move_to(|item| item.video.url == url);
+                            }
+                        }
+                        BackendMessage::Remove(item) => list.remove(&item),
+                        BackendMessage::Clear => list.clear(),
+                        BackendMessage::FinishedFetching | BackendMessage::Error(_) => (),
+                    }
+                }
+                actions.redraw_async().await;
+            }
+        });
+    }
+
+    fn move_current(&mut self, up: bool) {
+        let Some(current) = self.list.lock().get_current_item() else {
+            return;
+        };
+
+        *self.moved_url.lock() = Some(current.video.url.clone());
+        let watch_later = self.watch_later.clone();
+        let actions = self.actions.clone();
+        tokio::spawn(async move {
+            let move_result = watch_later.move_video(&current.video.url, up).await;
+            actions.redraw_or_error_async(move_result, true).await;
+        });
+    }
+
+    fn remove(&self, urls: Vec<String>) {
+        let watch_later = self.watch_later.clone();
+        let actions = self.actions.clone();
+        tokio::spawn(async move {
+            let remove_result = watch_later.remove(&urls).await;
+            actions.redraw_or_error_async(remove_result, true).await;
+        });
+    }
+
+    fn remove_current(&mut self) {
+        if let Some(current) = self.list.lock().get_current_item() {
+            self.remove(vec![current.video.url]);
+        }
+    }
+
+    // Videos are removed from the list when they're handed to the player
+    fn play_all(&mut self) {
+        let videos: Vec<_> = self
+            .list
+            .lock()
+            .iter()
+            .map(|item| item.video.clone())
+            .collect();
+        if videos.is_empty() {
+            return;
+        }
+
+        let urls = videos.iter().map(|video| video.url.clone()).collect();
+        self.launcher.play(videos, None, None);
+        self.remove(urls);
+    }
+
+    fn play_current(&mut self) {
+        if let Some(current) = self.list.lock().get_current_item() {
+            self.launcher.play(vec![current.video.clone()], None, None);
+            self.remove(vec![current.video.url]);
+        }
+    }
+
+    fn copy_current(&mut self) {
+        if let Some(current) = self.list.lock().get_current_item() {
+            self.actions
+                .copy_to_clipboard(self.config.clipboard(), current.video.url.clone());
+        }
+    }
+}
+
+impl Component for WatchLaterView {
+    fn draw(&mut self, f: &mut Frame, area: Rect) {
+        let list = self.list.lock();
+        let title = format!("Watch later ({})", list.iter().count());
+        let list = list
+            .list(area.height.into())
+            .block(Block::default().title(title))
+            .style(Style::default().fg(Color::White));
+        f.render_widget(list, area);
+    }
+
+    fn handle_event(&mut self, event: Event, size: Option<Size>) {
+        let height = size.map_or(30, |size| size.height.into());
+
+        let Event::Key(event) = event else {
+            return;
+        };
+
+        match event.code {
+            KeyCode::Up | KeyCode::Char('k') => self.list.lock().move_up(1),
+            KeyCode::Down | KeyCode::Char('j') => self.list.lock().move_down(1),
+            KeyCode::Char('d') => self.list.lock().move_down(height / 2),
+            KeyCode::Char('u') => self.list.lock().move_up(height / 2),
+            KeyCode::Char('g') => self.list.lock().move_top(),
+            KeyCode::Char('G') => self.list.lock().move_bottom(),
+            KeyCode::Char('K') => self.move_current(true),
+            KeyCode::Char('J') => self.move_current(false),
+            KeyCode::Char('x') => self.remove_current(),
+            KeyCode::Enter => self.play_all(),
+            KeyCode::Char('p') => self.play_current(),
+            KeyCode::Char('y') => self.copy_current(),
+            _ => return,
+        }
+
+        self.actions.redraw();
+    }
+
+    fn registered_events(&self) -> Vec<(String, String)> {
+        vec![
+            (String::from("j"), String::from("Down")),
+            (String::from("k"), String::from("Up")),
+            (String::from("J"), String::from("Move down")),
+            (String::from("K"), String::from("Move up")),
+            (String::from("x"), String::from("Remove")),
+            (String::from("Enter"), String::from("Play all")),
+            (String::from("p"), String::from("Play current")),
+            (String::from("y"), String::from("Copy url")),
+        ]
+    }
+}
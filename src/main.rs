@@ -1,66 +1,62 @@
 mod backend;
+mod browser;
+mod cli;
+mod clipboard;
 mod config;
 mod config_error;
+mod download;
 mod file_handler;
+mod history;
+mod hooks;
 mod interface;
+mod mpv;
+mod notification;
+mod playback;
+mod player;
+mod playlist;
+mod remote;
+mod starred;
+mod watch_later;
+mod yt_dlp;
 
 use std::{
     fs::{self, File},
     path::PathBuf,
+    process::ExitCode,
     str::FromStr,
 };
 
-use backend::{rss::RssBackend, Backend};
+use clap::Parser;
+use cli::Cli;
 use interface::{app::App, ui};
 
 use crossterm::{
     event::{DisableBracketedPaste, EnableBracketedPaste},
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
-use ratatui::{backend::CrosstermBackend, Terminal};
+use ratatui::{Terminal, backend::CrosstermBackend};
 use simplelog::{CombinedLogger, LevelFilter, WriteLogger};
 
 #[tokio::main]
-async fn main() {
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
     setup_logging();
 
-    let args: Vec<String> = std::env::args().collect();
-    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
-        print_help();
-        return;
-    }
-
-    let youtube_export_path = args
-        .iter()
-        .skip_while(|arg| arg.as_str() != "--import-youtube")
-        .nth(1);
-    if let Some(path) = youtube_export_path {
-        import_youtube_takeout(path).await;
-    } else {
-        run().await;
+    match cli.command {
+        Some(command) => cli::run(command).await.unwrap_or_else(|error| {
+            eprintln!("Error: {error}");
+            // 1 is used by commands to report an empty result
+            ExitCode::from(2)
+        }),
+        None => {
+            run(cli.player).await;
+            ExitCode::SUCCESS
+        }
     }
 }
 
-fn print_help() {
-    println!("Available options:");
-    println!("  -h|--help                 Show this help message.");
-    println!("  --import-youtube <path>   Import subscriptions csv from YouTube takeout");
-    println!("  --player <player>         Override player in config");
-}
-
-async fn import_youtube_takeout(path: &str) {
-    println!("Importing subscriptions...");
-    RssBackend::load()
-        .await
-        .expect("Failed to load config")
-        .import_youtube(path)
-        .await
-        .expect("Failed to import youtube takeout");
-    println!("Done.");
-}
-
-async fn run() {
+async fn run(player: Option<String>) {
     enable_raw_mode().expect("Failed to setup interface");
     let mut stdout = std::io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableBracketedPaste)
@@ -69,9 +65,11 @@ async fn run() {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend).expect("Failed to setup interface");
 
-    ui::create(&mut terminal, App::new)
-        .await
-        .expect("Failed to run ui");
+    ui::create(&mut terminal, |ui_sender, overlays| {
+        App::new(ui_sender, overlays, player)
+    })
+    .await
+    .expect("Failed to run ui");
 
     disable_raw_mode().expect("Failed to clean up");
     execute!(
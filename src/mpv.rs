@@ -0,0 +1,192 @@
+use crate::{file_handler, playback::Progress};
+
+use log::warn;
+use serde_json::{Value, json};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+use thiserror::Error;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{UnixStream, unix::OwnedWriteHalf},
+    process::Child,
+};
+
+const CONNECT_ATTEMPTS: usize = 50;
+const CONNECT_INTERVAL: Duration = Duration::from_millis(100);
+
+static SOCKET_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, Error)]
+pub enum MpvError {
+    #[error("Failed to communicate with mpv: {}", _0)]
+    Ipc(#[from] std::io::Error),
+
+    #[error("Failed to serialize mpv command: {}", _0)]
+    Serialize(#[from] serde_json::Error),
+
+    #[error(
+        "Not creating the mpv socket in {}, which has to be a directory that only you can access",
+        _0.display()
+    )]
+    InsecureDir(PathBuf),
+}
+
+// A unique path for the IPC socket of a new mpv instance in the runtime dir
+pub async fn socket_path() -> Result<PathBuf, MpvError> {
+    let dir = file_handler::find_runtime_dir();
+    if !file_handler::ensure_private_dir(&dir).await? {
+        return Err(MpvError::InsecureDir(dir));
+    }
+
+    let id = SOCKET_COUNTER.fetch_add(1, Ordering::Relaxed);
+    Ok(dir.join(format!("mpv-{}-{id}.sock", std::process::id())))
+}
+
+// Follows the playback of a running mpv instance until it exits. `on_connect` is called once mpv
+// accepts IPC connections on the socket, files are seeked to the position returned by `resume` when
+// loaded, and `on_queue_change` is called with the number of files left in the playlist. The last
+// known position of every played file is returned.
+pub async fn track(
+    child: &mut Child,
+    socket: &Path,
+    on_connect: impl FnOnce(),
+    resume: impl Fn(&str) -> Option<f64>,
+    on_queue_change: impl Fn(usize),
+) -> Result<Vec<Progress>, MpvError> {
+    let result = match connect(child, socket).await {
+        Some(stream) => {
+            on_connect();
+            track_stream(stream, resume, on_queue_change).await
+        }
+        None => {
+            warn!("Failed to connect to mpv socket {}", socket.display());
+            Ok(vec![])
+        }
+    };
+
+    child.wait().await?;
+    let _ = tokio::fs::remove_file(socket).await;
+    result
+}
+
+// Appends the urls to the playlist of the mpv instance listening on the socket
+pub async fn append(socket: &Path, urls: &[String]) -> Result<(), MpvError> {
+    let (_, mut writer) = UnixStream::connect(socket).await?.into_split();
+    for url in urls {
+        send(
+            &mut writer,
+            json!({ "command": ["loadfile", url, "append-play"] }),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+async fn connect(child: &mut Child, socket: &Path) -> Option<UnixStream> {
+    for _ in 0..CONNECT_ATTEMPTS {
+        if let Ok(stream) = UnixStream::connect(socket).await {
+            return Some(stream);
+        }
+        if !matches!(child.try_wait(), Ok(None)) {
+            return None;
+        }
+        tokio::time::sleep(CONNECT_INTERVAL).await;
+    }
+    None
+}
+
+async fn track_stream(
+    stream: UnixStream,
+    resume: impl Fn(&str) -> Option<f64>,
+    on_queue_change: impl Fn(usize),
+) -> Result<Vec<Progress>, MpvError> {
+    let (reader, mut writer) = stream.into_split();
+    let properties = [
+        "path",
+        "time-pos",
+        "duration",
+        "playlist-count",
+        "playlist-pos",
+    ];
+    for (id, property) in properties.iter().enumerate() {
+        send(
+            &mut writer,
+            json!({ "command": ["observe_property", id + 1, property] }),
+        )
+        .await?;
+    }
+
+    let mut progress: HashMap<String, Progress> = HashMap::new();
+    let mut path: Option<String> = None;
+    let (mut playlist_count, mut playlist_pos) = (0, 0);
+    let mut lines = BufReader::new(reader).lines();
+    while let Some(line) = lines.next_line().await? {
+        let Ok(message) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+
+        match (message["event"].as_str(), message["name"].as_str()) {
+            (Some("property-change"), Some("path")) => {
+                path = message["data"].as_str().map(str::to_owned);
+                seek_to_resume_position(&mut writer, path.as_deref(), &resume).await?;
+            }
+            (Some("property-change"), Some(name @ ("time-pos" | "duration"))) => {
+                let (Some(path), Some(value)) = (&path, message["data"].as_f64()) else {
+                    continue;
+                };
+
+                let entry = progress.entry(path.clone()).or_insert_with(|| Progress {
+                    url: path.clone(),
+                    position: 0.0,
+                    duration: None,
+                });
+                if name == "time-pos" {
+                    entry.position = value;
+                } else {
+                    entry.duration = Some(value);
+                }
+            }
+            (Some("property-change"), Some(name @ ("playlist-count" | "playlist-pos"))) => {
+                // The position is -1 when nothing is playing
+                let value = message["data"].as_i64().unwrap_or(0).max(0);
+                if name == "playlist-count" {
+                    playlist_count = value;
+                } else {
+                    playlist_pos = value;
+                }
+                on_queue_change(usize::try_from(playlist_count - playlist_pos).unwrap_or(0));
+            }
+            (Some("file-loaded"), _) => {
+                seek_to_resume_position(&mut writer, path.as_deref(), &resume).await?;
+            }
+            _ => (),
+        }
+    }
+
+    Ok(progress.into_values().collect())
+}
+
+// Seeking fails if the file hasn't been loaded yet, and the seek is therefore done both when the
+// path changes and when the file has been loaded since the connection might be established after
+// the file has been loaded
+async fn seek_to_resume_position(
+    writer: &mut OwnedWriteHalf,
+    path: Option<&str>,
+    resume: &impl Fn(&str) -> Option<f64>,
+) -> Result<(), MpvError> {
+    match path.and_then(resume) {
+        Some(position) => send(writer, json!({ "command": ["seek", position, "absolute"] })).await,
+        None => Ok(()),
+    }
+}
+
+async fn send(writer: &mut OwnedWriteHalf, command: Value) -> Result<(), MpvError> {
+    let mut command = serde_json::to_vec(&command)?;
+    command.push(b'\n');
+    writer.write_all(&command).await?;
+    Ok(())
+}
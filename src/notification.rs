@@ -0,0 +1,18 @@
+use std::io;
+use tokio::process::Command;
+
+// Shows a desktop notification through notify-send
+pub async fn notify(summary: &str, body: &str) -> io::Result<()> {
+    let status = Command::new("notify-send")
+        .arg("--app-name=tuibe")
+        .arg(summary)
+        .arg(body)
+        .status()
+        .await?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(status.to_string()))
+    }
+}
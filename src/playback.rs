@@ -0,0 +1,86 @@
+use super::file_handler::ConfigFileHandler;
+use crate::config_error::ConfigError;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+const PLAYBACK_NAME: &str = "playback";
+
+// Playback progress of videos played through mpv IPC, keyed by video url
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Playback {
+    // Videos that have been played past the watched threshold
+    pub watched: BTreeSet<String>,
+    // Position in seconds of videos that have been started but not watched
+    pub positions: HashMap<String, f64>,
+}
+
+pub struct Progress {
+    pub url: String,
+    pub position: f64,
+    pub duration: Option<f64>,
+}
+
+pub struct PlaybackHandler {
+    playback: Mutex<Playback>,
+    file_handler: tokio::sync::Mutex<ConfigFileHandler<Playback>>,
+}
+
+impl PlaybackHandler {
+    pub async fn load() -> Result<Self, ConfigError> {
+        let mut file_handler = ConfigFileHandler::from_state_file(PLAYBACK_NAME).await?;
+        let playback = file_handler.read().await?;
+
+        Ok(Self {
+            playback: Mutex::new(playback),
+            file_handler: tokio::sync::Mutex::new(file_handler),
+        })
+    }
+
+    pub fn is_watched(&self, url: &str) -> bool {
+        self.playback.lock().watched.contains(url)
+    }
+
+    pub fn watched(&self) -> Vec<String> {
+        self.playback.lock().watched.iter().cloned().collect()
+    }
+
+    // Position to resume the video from
+    pub fn position(&self, url: &str) -> Option<f64> {
+        self.playback.lock().positions.get(url).copied()
+    }
+
+    // Stores the progress and returns the urls of the videos that were watched past the
+    // threshold percentage
+    pub async fn record(
+        &self,
+        progress: Vec<Progress>,
+        watched_threshold: u8,
+    ) -> Result<Vec<String>, ConfigError> {
+        let mut watched = vec![];
+        let new_playback = {
+            let mut playback = self.playback.lock();
+            for progress in progress {
+                let percentage = progress
+                    .duration
+                    .filter(|duration| *duration > 0.0)
+                    .map(|duration| progress.position / duration * 100.0);
+
+                if percentage.is_some_and(|percentage| percentage >= f64::from(watched_threshold)) {
+                    playback.positions.remove(&progress.url);
+                    playback.watched.insert(progress.url.clone());
+                    watched.push(progress.url);
+                } else {
+                    playback.positions.insert(progress.url, progress.position);
+                }
+            }
+            playback.clone()
+        };
+
+        let mut file_handler = self.file_handler.lock().await;
+        file_handler.write(&new_playback).await?;
+        Ok(watched)
+    }
+}
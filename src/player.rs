@@ -0,0 +1,247 @@
+use crate::{
+    backend::Video,
+    config_error::ConfigError,
+    mpv::{self, MpvError},
+    playback::Progress,
+};
+
+use std::{ffi::OsStr, path::Path, process::Stdio};
+use thiserror::Error;
+use tokio::process::Command;
+
+#[derive(Debug, Error)]
+pub enum PlayerError {
+    #[error("Player command is empty")]
+    EmptyCommand,
+
+    #[error("Unterminated quote in player command: {}", _0)]
+    UnterminatedQuote(String),
+
+    #[error("Failed to run player: {}", _0)]
+    Run(#[from] std::io::Error),
+
+    #[error("{}", _0)]
+    Mpv(#[from] MpvError),
+
+    #[error("Failed to save playback: {}", _0)]
+    SavePlayback(#[from] ConfigError),
+
+    #[error("The player command only takes one video through {{url}}, use {{urls}} instead")]
+    SingleUrl,
+}
+
+// Templates with {url} are run once per video, one after another
+pub async fn play(template: &str, videos: &[Video], start: Option<u32>) -> Result<(), PlayerError> {
+    if takes_single_url(template) && videos.len() > 1 {
+        for video in videos {
+            command(template, std::slice::from_ref(video), start)?
+                .status()
+                .await?;
+        }
+    } else {
+        command(template, videos, start)?.status().await?;
+    }
+    Ok(())
+}
+
+// Plays the videos in mpv with an IPC server on the socket, see `mpv::track`
+pub async fn play_with_ipc(
+    template: &str,
+    videos: &[Video],
+    start: Option<u32>,
+    socket: &Path,
+    on_connect: impl FnOnce(),
+    resume: impl Fn(&str) -> Option<f64>,
+    on_queue_change: impl Fn(usize),
+) -> Result<Vec<Progress>, PlayerError> {
+    // The videos need to be played by the instance that is being tracked
+    if takes_single_url(template) && videos.len() > 1 {
+        return Err(PlayerError::SingleUrl);
+    }
+
+    let mut child = command(template, videos, start)?
+        .arg(format!("--input-ipc-server={}", socket.display()))
+        .spawn()?;
+    Ok(mpv::track(&mut child, socket, on_connect, resume, on_queue_change).await?)
+}
+
+// Appends the videos to the playlist of an mpv instance started with `play_with_ipc`
+pub async fn enqueue(socket: &Path, videos: &[Video]) -> Result<(), PlayerError> {
+    let urls: Vec<String> = videos.iter().map(|video| video.url.clone()).collect();
+    Ok(mpv::append(socket, &urls).await?)
+}
+
+fn takes_single_url(template: &str) -> bool {
+    template.contains("{url}") && !template.contains("{urls}")
+}
+
+pub fn is_mpv_template(template: &str) -> bool {
+    split_words(template)
+        .ok()
+        .and_then(|words| words.into_iter().next())
+        .is_some_and(|program| is_mpv(program.as_ref()))
+}
+
+// Builds the player command from a template such as "mpv --start={start} {urls}". The supported
+// placeholders are {url}, {urls}, {title}, {author} and {start}. Arguments containing {start} are
+// left out when playing from the beginning, and the urls are appended if the template doesn't
+// contain {url} or {urls}.
+fn command(template: &str, videos: &[Video], start: Option<u32>) -> Result<Command, PlayerError> {
+    let words = split_words(template)?;
+    let (program, template_args) = words.split_first().ok_or(PlayerError::EmptyCommand)?;
+
+    let urls: Vec<String> = videos.iter().map(|video| video.url.clone()).collect();
+    let first = videos.first();
+    let replace = |word: &str| {
+        word.replace("{url}", urls.first().map_or("", String::as_str))
+            .replace("{urls}", &urls.join(" "))
+            .replace("{title}", first.map_or("", |video| video.title.as_str()))
+            .replace("{author}", first.map_or("", |video| video.author.as_str()))
+            .replace("{start}", &start.unwrap_or(0).to_string())
+    };
+
+    let mut args = vec![];
+    for word in template_args {
+        if word == "{urls}" {
+            args.extend(urls.iter().cloned());
+        } else if !word.contains("{start}") || start.is_some() {
+            args.push(replace(word));
+        }
+    }
+
+    let has_urls = template_args
+        .iter()
+        .any(|word| word.contains("{url}") || word.contains("{urls}"));
+    let has_start = template_args.iter().any(|word| word.contains("{start}"));
+    if !has_urls {
+        match start {
+            Some(start) if !has_start => args.extend(start_args(program, urls, start)),
+            _ => args.extend(urls),
+        }
+    }
+
+    let mut command = Command::new(program);
+    command
+        .args(args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    Ok(command)
+}
+
+// mpv accepts a start position as an option while other players get it through the url
+fn start_args(program: &str, urls: Vec<String>, start: u32) -> Vec<String> {
+    if is_mpv(program.as_ref()) {
+        let mut args = vec![format!("--start={start}")];
+        args.extend(urls);
+        args
+    } else {
+        // Local files are played from the beginning since they have no url parameters
+        urls.into_iter()
+            .map(|url| {
+                if !url.contains("://") {
+                    return url;
+                }
+                let separator = if url.contains('?') { '&' } else { '?' };
+                format!("{url}{separator}t={start}s")
+            })
+            .collect()
+    }
+}
+
+fn is_mpv(program: &OsStr) -> bool {
+    Path::new(program)
+        .file_name()
+        .is_some_and(|file_name| file_name == "mpv")
+}
+
+// Splits the template into words separated by whitespace. Single and double quotes can be used to
+// include whitespace in a word.
+fn split_words(template: &str) -> Result<Vec<String>, PlayerError> {
+    let mut words = vec![];
+    let mut word: Option<String> = None;
+    let mut quote = None;
+    for character in template.chars() {
+        match (quote, character) {
+            (Some(open), character) if character == open => quote = None,
+            (Some(_), character) => word.get_or_insert_default().push(character),
+            (None, '"' | '\'') => {
+                quote = Some(character);
+                word.get_or_insert_default();
+            }
+            (None, character) if character.is_whitespace() => words.extend(word.take()),
+            (None, character) => word.get_or_insert_default().push(character),
+        }
+    }
+
+    if quote.is_some() {
+        return Err(PlayerError::UnterminatedQuote(template.to_owned()));
+    }
+
+    words.extend(word);
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn video(url: &str) -> Video {
+        Video {
+            title: format!("Title of {url}"),
+            ..Video::from_url(url)
+        }
+    }
+
+    fn args(template: &str, videos: &[Video], start: Option<u32>) -> Vec<String> {
+        let command = command(template, videos, start).unwrap();
+        command
+            .as_std()
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn appends_urls_without_placeholders() {
+        let videos = [video("https://a"), video("https://b")];
+        assert_eq!(args("mpv", &videos, None), ["https://a", "https://b"]);
+        assert_eq!(
+            args("mpv", &videos, Some(30)),
+            ["--start=30", "https://a", "https://b"]
+        );
+        assert_eq!(args("vlc", &videos[..1], Some(30)), ["https://a?t=30s"]);
+    }
+
+    #[test]
+    fn replaces_placeholders() {
+        let videos = [video("https://a"), video("https://b")];
+        assert_eq!(
+            args("mpv --title='{title}' {urls}", &videos, None),
+            ["--title=Title of https://a", "https://a", "https://b"]
+        );
+        assert_eq!(
+            args("player --from={start} {url}", &videos[..1], None),
+            ["https://a"]
+        );
+        assert_eq!(
+            args("player --from={start} {url}", &videos[..1], Some(5)),
+            ["--from=5", "https://a"]
+        );
+    }
+
+    #[test]
+    fn detects_templates_taking_a_single_url() {
+        assert!(takes_single_url("player {url}"));
+        assert!(!takes_single_url("player {urls}"));
+        assert!(!takes_single_url("mpv"));
+    }
+
+    #[test]
+    fn rejects_unterminated_quotes() {
+        assert!(matches!(
+            split_words("mpv 'a b"),
+            Err(PlayerError::UnterminatedQuote(_))
+        ));
+        assert_eq!(split_words("mpv 'a b' c").unwrap(), ["mpv", "a b", "c"]);
+    }
+}
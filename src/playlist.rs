@@ -0,0 +1,208 @@
+use crate::backend::{Video, single_line};
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+use tokio::fs;
+
+#[derive(Debug, Error)]
+pub enum PlaylistError {
+    #[error("Unknown playlist format of {}, expected .m3u, .m3u8 or .xspf", _0)]
+    UnknownFormat(String),
+
+    #[error("Failed to write playlist: {}", _0)]
+    Write(#[from] io::Error),
+}
+
+#[derive(Clone, Copy)]
+pub enum PlaylistFormat {
+    M3u8,
+    Xspf,
+}
+
+impl PlaylistFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "m3u" | "m3u8" => Some(Self::M3u8),
+            "xspf" => Some(Self::Xspf),
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::M3u8 => "m3u8",
+            Self::Xspf => "xspf",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::M3u8 => "M3U8",
+            Self::Xspf => "XSPF",
+        }
+    }
+}
+
+struct PlaylistEntry {
+    title: String,
+    author: String,
+    // Length in seconds, 0 if unknown
+    length: u32,
+    location: String,
+    local: bool,
+}
+
+// Writes the videos to a playlist in the format matching the extension of the path. Videos are
+// referenced by the path returned by `local_path` when there is one and by their urls otherwise.
+pub async fn export(
+    path: &Path,
+    videos: &[Video],
+    local_path: impl Fn(&str) -> Option<PathBuf>,
+) -> Result<(), PlaylistError> {
+    let format = PlaylistFormat::from_path(path)
+        .ok_or_else(|| PlaylistError::UnknownFormat(path.display().to_string()))?;
+
+    let entries: Vec<PlaylistEntry> = videos
+        .iter()
+        .map(|video| {
+            let local_path = local_path(&video.url);
+            PlaylistEntry {
+                title: single_line(&video.title),
+                author: single_line(&video.author),
+                length: video.length,
+                local: local_path.is_some(),
+                location: local_path.map_or_else(
+                    || video.url.clone(),
+                    |path| path.to_string_lossy().into_owned(),
+                ),
+            }
+        })
+        .collect();
+
+    let playlist = match format {
+        PlaylistFormat::M3u8 => render_m3u8(&entries),
+        PlaylistFormat::Xspf => render_xspf(&entries),
+    };
+    fs::write(path, playlist).await?;
+    Ok(())
+}
+
+fn render_m3u8(entries: &[PlaylistEntry]) -> String {
+    let mut playlist = String::from("#EXTM3U\n");
+    for entry in entries {
+        // -1 marks the length as unknown
+        let length = match entry.length {
+            0 => -1,
+            length => i64::from(length),
+        };
+        playlist.push_str(&format!(
+            "#EXTINF:{length},{} - {}\n{}\n",
+            entry.author, entry.title, entry.location
+        ));
+    }
+    playlist
+}
+
+fn render_xspf(entries: &[PlaylistEntry]) -> String {
+    let mut playlist = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<playlist version=\"1\" xmlns=\"http://xspf.org/ns/0/\">\n",
+        "  <trackList>\n",
+    ));
+
+    for entry in entries {
+        // Locations are URIs in XSPF
+        let location = if entry.local {
+            format!("file://{}", percent_encode(&entry.location))
+        } else {
+            entry.location.clone()
+        };
+
+        playlist.push_str("    <track>\n");
+        playlist.push_str(&format!(
+            "      <location>{}</location>\n",
+            escape_xml(&location)
+        ));
+        playlist.push_str(&format!(
+            "      <title>{}</title>\n",
+            escape_xml(&entry.title)
+        ));
+        playlist.push_str(&format!(
+            "      <creator>{}</creator>\n",
+            escape_xml(&entry.author)
+        ));
+        if entry.length > 0 {
+            // The duration is in milliseconds
+            playlist.push_str(&format!(
+                "      <duration>{}</duration>\n",
+                u64::from(entry.length) * 1000
+            ));
+        }
+        playlist.push_str("    </track>\n");
+    }
+
+    playlist.push_str("  </trackList>\n</playlist>\n");
+    playlist
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn percent_encode(path: &str) -> String {
+    path.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                char::from(byte).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(title: &str, location: &str, local: bool) -> PlaylistEntry {
+        PlaylistEntry {
+            title: title.to_owned(),
+            author: String::from("Tom & Jerry"),
+            length: 0,
+            location: location.to_owned(),
+            local,
+        }
+    }
+
+    #[test]
+    fn renders_m3u8() {
+        let entries = [entry("<b>\"Title\"</b>", "https://a?b=1&c=2", false)];
+        assert_eq!(
+            render_m3u8(&entries),
+            "#EXTM3U\n#EXTINF:-1,Tom & Jerry - <b>\"Title\"</b>\nhttps://a?b=1&c=2\n"
+        );
+    }
+
+    #[test]
+    fn escapes_xspf() {
+        let entries = [
+            entry("<b>\"Title\"</b> 'x'", "https://a?b=1&c=2", false),
+            entry("Local", "/videos/a b&c.mkv", true),
+        ];
+        let playlist = render_xspf(&entries);
+        assert!(playlist.contains("<location>https://a?b=1&amp;c=2</location>"));
+        assert!(
+            playlist.contains("<title>&lt;b&gt;&quot;Title&quot;&lt;/b&gt; &apos;x&apos;</title>")
+        );
+        assert!(playlist.contains("<creator>Tom &amp; Jerry</creator>"));
+        assert!(playlist.contains("<location>file:///videos/a%20b%26c.mkv</location>"));
+        assert!(!playlist.contains("<duration>"));
+    }
+}
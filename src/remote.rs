@@ -0,0 +1,111 @@
+use crate::file_handler;
+
+use clap::Subcommand;
+use serde::{Deserialize, Serialize};
+use std::{io, path::PathBuf};
+use thiserror::Error;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{UnixListener, UnixStream},
+};
+
+const SOCKET_NAME: &str = "remote.sock";
+
+#[derive(Debug, Error)]
+pub enum RemoteError {
+    #[error("Failed to connect to tuibe, is it running? {}", _0)]
+    Connect(#[source] io::Error),
+
+    #[error("Failed to listen for remote commands: {}", _0)]
+    Listen(#[source] io::Error),
+
+    #[error(
+        "Not listening for remote commands in {}, which has to be a directory that only you can access",
+        _0.display()
+    )]
+    InsecureDir(PathBuf),
+
+    #[error("Another instance of tuibe is already listening for remote commands")]
+    AlreadyListening,
+
+    #[error("Failed to communicate with tuibe: {}", _0)]
+    Io(#[from] io::Error),
+
+    #[error("Invalid remote message: {}", _0)]
+    Parse(#[from] serde_json::Error),
+
+    #[error("{}", _0)]
+    Command(String),
+}
+
+// Commands sent as JSON lines over the socket, such as {"command":"add","url":"..."}
+#[derive(Clone, Subcommand, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "lowercase")]
+pub enum RemoteCommand {
+    /// Subscribe to a feed
+    Add { url: String },
+
+    /// Unsubscribe from a feed
+    Remove { url: String },
+
+    /// Fetch all feeds again
+    Refresh,
+
+    /// Play a video, or append it to the running mpv instance in queue mode
+    Queue { url: String },
+
+    /// Quit tuibe
+    Quit,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RemoteResponse {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+pub fn socket_path() -> PathBuf {
+    file_handler::find_runtime_dir().join(SOCKET_NAME)
+}
+
+// Binds the socket, replacing sockets left behind by instances that didn't exit cleanly
+pub async fn listen() -> Result<UnixListener, RemoteError> {
+    let path = socket_path();
+    if let Some(dir) = path.parent()
+        && !file_handler::ensure_private_dir(dir)
+            .await
+            .map_err(RemoteError::Listen)?
+    {
+        return Err(RemoteError::InsecureDir(dir.to_owned()));
+    }
+
+    if path.exists() {
+        if UnixStream::connect(&path).await.is_ok() {
+            return Err(RemoteError::AlreadyListening);
+        }
+        tokio::fs::remove_file(&path)
+            .await
+            .map_err(RemoteError::Listen)?;
+    }
+
+    UnixListener::bind(&path).map_err(RemoteError::Listen)
+}
+
+pub async fn send(command: &RemoteCommand) -> Result<(), RemoteError> {
+    let stream = UnixStream::connect(socket_path())
+        .await
+        .map_err(RemoteError::Connect)?;
+    let (reader, mut writer) = stream.into_split();
+
+    let mut request = serde_json::to_string(command)?;
+    request.push('\n');
+    writer.write_all(request.as_bytes()).await?;
+
+    let mut response = String::new();
+    BufReader::new(reader).read_line(&mut response).await?;
+    let response: RemoteResponse = serde_json::from_str(&response)?;
+    match response.error {
+        Some(error) => Err(RemoteError::Command(error)),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,69 @@
+use super::file_handler::ConfigFileHandler;
+use crate::{
+    backend::{
+        Video,
+        channel::{BackendMessage, BackendReceiver, BackendSender},
+    },
+    config_error::ConfigError,
+};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+const STARRED_NAME: &str = "starred";
+
+// Starred videos are stored in full so that they're kept after they've left the feeds
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Starred {
+    pub videos: Vec<Video>,
+}
+
+pub struct StarredHandler {
+    starred: Mutex<Starred>,
+    file_handler: tokio::sync::Mutex<ConfigFileHandler<Starred>>,
+    sender: BackendSender<Video>,
+}
+
+impl StarredHandler {
+    pub async fn load() -> Result<Self, ConfigError> {
+        let mut file_handler = ConfigFileHandler::from_state_file(STARRED_NAME).await?;
+        let starred = file_handler.read().await?;
+
+        Ok(Self {
+            starred: Mutex::new(starred),
+            file_handler: tokio::sync::Mutex::new(file_handler),
+            sender: BackendSender::new(),
+        })
+    }
+
+    pub fn subscribe(&self) -> BackendReceiver<Video> {
+        let videos = self.starred.lock().videos.clone();
+        self.sender.subscribe(videos)
+    }
+
+    // Stars the video if it isn't starred and unstars it otherwise
+    pub async fn toggle(&self, video: Video) -> Result<(), ConfigError> {
+        let new_starred = {
+            let mut starred = self.starred.lock();
+            match starred
+                .videos
+                .iter()
+                .position(|existing| existing.url == video.url)
+            {
+                Some(position) => {
+                    let video = starred.videos.remove(position);
+                    self.sender.send(BackendMessage::Remove(video));
+                }
+                None => {
+                    starred.videos.push(video.clone());
+                    self.sender.send(BackendMessage::New(video));
+                }
+            }
+            starred.clone()
+        };
+
+        let mut file_handler = self.file_handler.lock().await;
+        file_handler.write(&new_starred).await
+    }
+}
@@ -0,0 +1,154 @@
+use super::file_handler::ConfigFileHandler;
+use crate::{
+    backend::{
+        Video,
+        channel::{BackendMessage, BackendReceiver, BackendSender},
+    },
+    config_error::ConfigError,
+};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+const WATCH_LATER_NAME: &str = "watch_later";
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatchLater {
+    pub videos: Vec<Video>,
+}
+
+// A video in the watch later list together with its position in the list
+#[derive(Clone)]
+pub struct WatchLaterItem {
+    pub position: usize,
+    pub video: Video,
+}
+
+pub struct WatchLaterHandler {
+    watch_later: Mutex<WatchLater>,
+    file_handler: tokio::sync::Mutex<ConfigFileHandler<WatchLater>>,
+    sender: BackendSender<WatchLaterItem>,
+}
+
+impl WatchLaterHandler {
+    pub async fn load() -> Result<Self, ConfigError> {
+        let mut file_handler = ConfigFileHandler::from_state_file(WATCH_LATER_NAME).await?;
+        let watch_later = file_handler.read().await?;
+
+        Ok(Self {
+            watch_later: Mutex::new(watch_later),
+            file_handler: tokio::sync::Mutex::new(file_handler),
+            sender: BackendSender::new(),
+        })
+    }
+
+    pub fn subscribe(&self) -> BackendReceiver<WatchLaterItem> {
+        let items = Self::items(&self.watch_later.lock().videos);
+        self.sender.subscribe(items)
+    }
+
+    pub fn contains(&self, url: &str) -> bool {
+        self.watch_later
+            .lock()
+            .videos
+            .iter()
+            .any(|video| video.url == url)
+    }
+
+    pub async fn add(&self, video: Video) -> Result<(), ConfigError> {
+        let new_watch_later = {
+            let mut watch_later = self.watch_later.lock();
+            if watch_later
+                .videos
+                .iter()
+                .any(|existing| existing.url == video.url)
+            {
+                return Ok(());
+            }
+
+            watch_later.videos.push(video.clone());
+            self.sender.send(BackendMessage::New(WatchLaterItem {
+                position: watch_later.videos.len() - 1,
+                video,
+            }));
+            watch_later.clone()
+        };
+
+        self.save(&new_watch_later).await
+    }
+
+    pub async fn remove(&self, urls: &[String]) -> Result<(), ConfigError> {
+        let new_watch_later = {
+            let mut watch_later = self.watch_later.lock();
+            let items = Self::items(&watch_later.videos);
+            watch_later
+                .videos
+                .retain(|video| !urls.contains(&video.url));
+
+            // The items after a removed item are moved up in the list
+            for item in items {
+                if urls.contains(&item.video.url) {
+                    self.sender.send(BackendMessage::Remove(item));
+                } else if let Some(position) = watch_later
+                    .videos
+                    .iter()
+                    .position(|video| video.url == item.video.url)
+                    && position != item.position
+                {
+                    self.sender.send(BackendMessage::Update(WatchLaterItem {
+                        position,
+                        video: item.video,
+                    }));
+                }
+            }
+            watch_later.clone()
+        };
+
+        self.save(&new_watch_later).await
+    }
+
+    // Moves the video up or down in the list by swapping it with its neighbour
+    pub async fn move_video(&self, url: &str, up: bool) -> Result<(), ConfigError> {
+        let new_watch_later = {
+            let mut watch_later = self.watch_later.lock();
+            let videos = &mut watch_later.videos;
+            let Some(position) = videos.iter().position(|video| video.url == url) else {
+                return Ok(());
+            };
+            let other_position = if up {
+                position.checked_sub(1)
+            } else {
+                Some(position + 1).filter(|position| *position < videos.len())
+            };
+            let Some(other_position) = other_position else {
+                return Ok(());
+            };
+
+            videos.swap(position, other_position);
+            for position in [position, other_position] {
+                self.sender.send(BackendMessage::Update(WatchLaterItem {
+                    position,
+                    video: videos[position].clone(),
+                }));
+            }
+            watch_later.clone()
+        };
+
+        self.save(&new_watch_later).await
+    }
+
+    fn items(videos: &[Video]) -> Vec<WatchLaterItem> {
+        videos
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(position, video)| WatchLaterItem { position, video })
+            .collect()
+    }
+
+    async fn save(&self, watch_later: &WatchLater) -> Result<(), ConfigError> {
+        let mut file_handler = self.file_handler.lock().await;
+        file_handler.write(watch_later).await
+    }
+}
@@ -0,0 +1,183 @@
+use crate::backend::VideoKind;
+
+use std::{path::PathBuf, process::Stdio};
+use thiserror::Error;
+use tokio::{
+    io::{AsyncBufReadExt, BufReader},
+    process::Command,
+    select,
+    sync::Notify,
+};
+
+// Prefixes of the lines printed by yt-dlp that are parsed while downloading
+const PROGRESS_PREFIX: &str = "tuibe-progress:";
+const FILE_PREFIX: &str = "tuibe-file:";
+
+#[derive(Debug, Error)]
+pub enum YtDlpError {
+    #[error("Failed to run yt-dlp: {}", _0)]
+    Run(#[from] std::io::Error),
+
+    #[error("yt-dlp failed: {}", _0)]
+    Failed(String),
+
+    #[error("Download was cancelled")]
+    Cancelled,
+}
+
+#[derive(Clone)]
+pub struct Probe {
+    pub duration: Option<u32>,
+    pub kind: Option<VideoKind>,
+}
+
+#[derive(Clone, Default)]
+pub struct DownloadProgress {
+    pub percent: f32,
+    pub speed: String,
+    pub eta: String,
+}
+
+// Downloads the video and returns the path of the downloaded file. The download is stopped when
+// `cancel` is notified.
+pub async fn download(
+    yt_dlp: &str,
+    url: &str,
+    format: Option<&str>,
+    output: &str,
+    cancel: &Notify,
+    on_progress: impl Fn(DownloadProgress),
+) -> Result<PathBuf, YtDlpError> {
+    let mut command = Command::new(yt_dlp);
+    command
+        .args(["--newline", "--no-warnings", "--no-colors", "--progress"])
+        .arg("--progress-template")
+        .arg(format!(
+            "download:{PROGRESS_PREFIX}%(progress._percent_str)s|%(progress._speed_str)s|%(progress._eta_str)s"
+        ))
+        .arg("--print")
+        .arg(format!("after_move:{FILE_PREFIX}%(filepath)s"))
+        .arg("--output")
+        .arg(output);
+    if let Some(format) = format {
+        command.arg("--format").arg(format);
+    }
+
+    let mut child = command
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+
+    // Progress is written to either stdout or stderr depending on the yt-dlp version
+    let mut stdout = child
+        .stdout
+        .take()
+        .map(|stdout| BufReader::new(stdout).lines());
+    let mut stderr = child
+        .stderr
+        .take()
+        .map(|stderr| BufReader::new(stderr).lines());
+    let mut path = None;
+    let mut error = None;
+    while stdout.is_some() || stderr.is_some() {
+        let line = select! {
+            line = next_line(&mut stdout), if stdout.is_some() => line,
+            line = next_line(&mut stderr), if stderr.is_some() => line,
+            _ = cancel.notified() => {
+                child.kill().await?;
+                return Err(YtDlpError::Cancelled);
+            }
+        };
+        let Some(line) = line else {
+            continue;
+        };
+
+        if let Some(progress) = line.strip_prefix(PROGRESS_PREFIX) {
+            on_progress(parse_progress(progress));
+        } else if let Some(file) = line.strip_prefix(FILE_PREFIX) {
+            path = Some(PathBuf::from(file));
+        } else if let Some(message) = line.strip_prefix("ERROR:") {
+            error = Some(message.trim().to_string());
+        }
+    }
+
+    let status = child.wait().await?;
+    match (status.success(), path) {
+        (true, Some(path)) => Ok(path),
+        (true, None) => Err(YtDlpError::Failed(String::from(
+            "The path of the downloaded file is unknown",
+        ))),
+        (false, _) => Err(YtDlpError::Failed(
+            error.unwrap_or_else(|| status.to_string()),
+        )),
+    }
+}
+
+// Reads the next line and drops the reader when the stream has ended
+async fn next_line<R: AsyncBufReadExt + Unpin>(
+    lines: &mut Option<tokio::io::Lines<R>>,
+) -> Option<String> {
+    let line = match lines {
+        Some(reader) => reader.next_line().await.ok().flatten(),
+        None => None,
+    };
+    if line.is_none() {
+        *lines = None;
+    }
+    line
+}
+
+fn parse_progress(progress: &str) -> DownloadProgress {
+    let mut fields = progress.split('|').map(str::trim);
+    let percent = fields
+        .next()
+        .and_then(|percent| percent.trim_end_matches('%').parse().ok())
+        .unwrap_or_default();
+
+    DownloadProgress {
+        percent,
+        speed: fields.next().unwrap_or_default().to_string(),
+        eta: fields.next().unwrap_or_default().to_string(),
+    }
+}
+
+pub async fn probe(yt_dlp: &str, url: &str) -> Result<Probe, YtDlpError> {
+    let output = Command::new(yt_dlp)
+        .args(["--skip-download", "--no-warnings", "--print"])
+        .arg("%(duration)s %(live_status)s %(width)s %(height)s")
+        .arg(url)
+        .stdin(Stdio::null())
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(YtDlpError::Failed(error.trim().to_string()));
+    }
+
+    // yt-dlp prints "NA" for fields that are unavailable
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut fields = stdout.split_whitespace();
+    let duration = fields
+        .next()
+        .and_then(|duration| duration.parse::<f64>().ok())
+        .map(|duration| duration.round() as u32);
+    let live_status = fields.next();
+    let width = fields.next().and_then(|width| width.parse::<u32>().ok());
+    let height = fields.next().and_then(|height| height.parse::<u32>().ok());
+
+    let kind = match (live_status, duration, width, height) {
+        (Some("is_live"), _, _, _) => Some(VideoKind::Live),
+        (Some("is_upcoming"), _, _, _) => Some(VideoKind::Upcoming),
+        (_, Some(duration), Some(width), Some(height)) if height > width && duration <= 180 => {
+            Some(VideoKind::Short)
+        }
+        (Some("NA") | None, _, _, _) => None,
+        _ => Some(VideoKind::Regular),
+    };
+
+    Ok(Probe { duration, kind })
+}